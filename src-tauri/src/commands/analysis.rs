@@ -0,0 +1,371 @@
+// Vault analysis for patto-mobile
+// Heavier workspace-wide scans used for maintenance screens
+
+use crate::commands::files::{collect_patto_files as collect_file_entries, FileEntry, LinkCache};
+use crate::commands::notes::{check_note_size, decode_note_bytes};
+use crate::commands::tasks::{
+    collect_all_tasks_with_progress, collect_patto_files, extract_tasks_from_content, TaskCache,
+};
+use patto::parser;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Number of hash functions in a MinHash signature
+const MINHASH_SIZE: usize = 64;
+
+/// Words per shingle
+const SHINGLE_SIZE: usize = 3;
+
+/// Pair of notes with similar content
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicatePair {
+    pub file_a: String,
+    pub file_b: String,
+    pub similarity: f32,
+}
+
+/// Find pairs of notes whose estimated content similarity exceeds the threshold (0.0-1.0)
+#[tauri::command]
+pub async fn find_duplicate_notes(
+    root: PathBuf,
+    similarity_threshold: f32,
+) -> Result<Vec<DuplicatePair>, String> {
+    tokio::task::spawn_blocking(move || {
+        let files = collect_patto_files(&root).map_err(|e| e.to_string())?;
+
+        // Fingerprint every note in parallel
+        let signatures: Vec<(String, [u64; MINHASH_SIZE])> = files
+            .into_par_iter()
+            .filter_map(|file_path| {
+                let content = fs::read_to_string(root.join(&file_path)).ok()?;
+                let signature = minhash_signature(&content)?;
+                Some((file_path, signature))
+            })
+            .collect();
+
+        let mut pairs: Vec<DuplicatePair> = (0..signatures.len())
+            .into_par_iter()
+            .flat_map_iter(|i| {
+                let (file_a, sig_a) = &signatures[i];
+                signatures[i + 1..]
+                    .iter()
+                    .filter_map(move |(file_b, sig_b)| {
+                        let same = sig_a
+                            .iter()
+                            .zip(sig_b.iter())
+                            .filter(|(a, b)| a == b)
+                            .count();
+                        let similarity = same as f32 / MINHASH_SIZE as f32;
+                        (similarity >= similarity_threshold).then(|| DuplicatePair {
+                            file_a: file_a.clone(),
+                            file_b: file_b.clone(),
+                            similarity,
+                        })
+                    })
+            })
+            .collect();
+
+        // Most similar first
+        pairs.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+
+        Ok(pairs)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Hub notes and orphans in the link graph
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkExtremes {
+    /// Notes with the most inbound links, `backlink_count` filled in
+    pub most_linked: Vec<FileEntry>,
+    /// Notes with no inbound and no outbound links
+    pub orphans: Vec<FileEntry>,
+}
+
+/// Report the `top_n` most linked notes and all orphaned notes
+/// Built from the backlink index so the counts match the file list
+#[tauri::command]
+pub async fn get_link_extremes(
+    app: AppHandle,
+    root: PathBuf,
+    top_n: usize,
+) -> Result<LinkExtremes, String> {
+    tokio::task::spawn_blocking(move || {
+        let links = app.state::<LinkCache>();
+        let entries = collect_file_entries(&root).map_err(|e| e.to_string())?;
+        let inbound = links.backlink_counts(&root)?;
+
+        let mut most_linked = Vec::new();
+        let mut orphans = Vec::new();
+        for mut entry in entries {
+            entry.backlink_count = inbound.get(&entry.path).copied().unwrap_or(0);
+            // Served from the cache `backlink_counts` just filled
+            let has_outbound = !links.outbound_links(&root, &entry.path).is_empty();
+
+            if entry.backlink_count > 0 {
+                most_linked.push(entry);
+            } else if !has_outbound {
+                orphans.push(entry);
+            }
+        }
+
+        most_linked.sort_by(|a, b| b.backlink_count.cmp(&a.backlink_count));
+        most_linked.truncate(top_n);
+        orphans.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(LinkExtremes {
+            most_linked,
+            orphans,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Summary numbers for the workspace info panel
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceStats {
+    pub file_count: usize,
+    pub total_bytes: u64,
+    /// Most recently modified note and its mtime (seconds since the epoch)
+    pub newest_path: Option<String>,
+    pub newest_modified: Option<u64>,
+    /// Least recently modified note and its mtime
+    pub oldest_path: Option<String>,
+    pub oldest_modified: Option<u64>,
+    pub total_tasks: usize,
+}
+
+/// Count notes, bytes and tasks and find the newest and oldest note in one pass
+#[tauri::command]
+pub async fn workspace_stats(root: PathBuf) -> Result<WorkspaceStats, String> {
+    tokio::task::spawn_blocking(move || {
+        let entries = collect_file_entries(&root).map_err(|e| e.to_string())?;
+        let mut stats = WorkspaceStats::default();
+
+        for entry in entries {
+            stats.file_count += 1;
+            stats.total_bytes += entry.size_bytes;
+
+            // Large notes count towards size but aren't parsed for tasks
+            let full_path = root.join(&entry.path);
+            if check_note_size(&full_path, &entry.path, None).is_ok() {
+                if let Ok(content) = fs::read_to_string(&full_path) {
+                    stats.total_tasks += extract_tasks_from_content(&content, &entry.path).len();
+                }
+            }
+
+            if stats
+                .newest_modified
+                .is_none_or(|t| entry.modified_time > t)
+            {
+                stats.newest_modified = Some(entry.modified_time);
+                stats.newest_path = Some(entry.path.clone());
+            }
+            if stats
+                .oldest_modified
+                .is_none_or(|t| entry.modified_time < t)
+            {
+                stats.oldest_modified = Some(entry.modified_time);
+                stats.oldest_path = Some(entry.path);
+            }
+        }
+
+        Ok(stats)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Kind of problem found by `vault_health_check`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HealthIssueKind {
+    NotUtf8,
+    Empty,
+    TooLarge,
+    ParseError,
+}
+
+/// Problem with a single note
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthIssue {
+    pub path: String,
+    pub issue: HealthIssueKind,
+    pub detail: String,
+}
+
+/// Scan every note for encoding, size and parse problems
+#[tauri::command]
+pub async fn vault_health_check(root: PathBuf) -> Result<Vec<HealthIssue>, String> {
+    tokio::task::spawn_blocking(move || {
+        let files = collect_patto_files(&root).map_err(|e| e.to_string())?;
+
+        let mut issues: Vec<HealthIssue> = files
+            .into_par_iter()
+            .flat_map_iter(|file_path| note_health_issues(&root, file_path))
+            .collect();
+
+        issues.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(issues)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+fn note_health_issues(root: &Path, file_path: String) -> Vec<HealthIssue> {
+    let full_path = root.join(&file_path);
+    let issue = |issue, detail: String| HealthIssue {
+        path: file_path.clone(),
+        issue,
+        detail,
+    };
+
+    // Oversized notes aren't read at all, like everywhere else
+    if let Err(e) = check_note_size(&full_path, &file_path, None) {
+        return vec![issue(HealthIssueKind::TooLarge, e)];
+    }
+
+    let bytes = match fs::read(&full_path) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            log::warn!("Skipping unreadable note {}: {}", file_path, e);
+            return Vec::new();
+        }
+    };
+
+    if bytes.is_empty() {
+        return vec![issue(HealthIssueKind::Empty, "File is empty".to_string())];
+    }
+
+    let note = decode_note_bytes(&bytes);
+    let mut issues = Vec::new();
+    if note.lossy || note.encoding != "UTF-8" {
+        issues.push(issue(
+            HealthIssueKind::NotUtf8,
+            format!(
+                "Decoded as {}{}",
+                note.encoding,
+                if note.lossy {
+                    " with invalid bytes replaced"
+                } else {
+                    ""
+                }
+            ),
+        ));
+    }
+
+    for error in parser::parse_text(&note.content).parse_errors {
+        issues.push(issue(HealthIssueKind::ParseError, error.to_string()));
+    }
+
+    issues
+}
+
+/// Progress event for `rebuild_indices`
+#[derive(Debug, Clone, Serialize)]
+pub struct RebuildProgress {
+    pub stage: String,
+    pub processed: usize,
+    pub total: usize,
+    pub percent: u32,
+}
+
+/// Counts from a full index rebuild
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexStats {
+    pub notes: usize,
+    pub tasks: usize,
+    /// Links between notes in the rebuilt backlink index
+    pub links: usize,
+}
+
+/// Drop all cached indices and rebuild them from disk, emitting `rebuild-progress` events
+/// For when files changed outside the app and the caches may have drifted
+/// Recently opened files aren't tracked on the Rust side, so there is nothing else to revalidate.
+#[tauri::command]
+pub async fn rebuild_indices(app: AppHandle, root: PathBuf) -> Result<IndexStats, String> {
+    tokio::task::spawn_blocking(move || {
+        let files = collect_patto_files(&root).map_err(|e| e.to_string())?;
+        let total = files.len();
+        let emit = |stage: &str, processed: usize| {
+            let percent = if total > 0 {
+                (processed * 100 / total) as u32
+            } else {
+                100
+            };
+            let _ = app.emit(
+                "rebuild-progress",
+                RebuildProgress {
+                    stage: stage.to_string(),
+                    processed,
+                    total,
+                    percent,
+                },
+            );
+        };
+
+        // Links: repopulate the backlink index the file list and link reports read from
+        emit("Indexing links", 0);
+        let link_cache = app.state::<LinkCache>();
+        link_cache.clear();
+        let links = link_cache.backlink_counts(&root)?.values().sum::<u32>() as usize;
+        emit("Indexing links", total);
+
+        // Tasks
+        emit("Indexing tasks", 0);
+        let cache = app.state::<TaskCache>();
+        cache.clear();
+        let tasks = collect_all_tasks_with_progress(&root, None, Some(cache.inner()), None)?;
+        emit("Indexing tasks", total);
+
+        Ok(IndexStats {
+            notes: total,
+            tasks: tasks.len(),
+            links,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// MinHash signature over word shingles, `None` for notes without any words
+fn minhash_signature(content: &str) -> Option<[u64; MINHASH_SIZE]> {
+    let words: Vec<String> = content
+        .split_whitespace()
+        .map(|w| w.to_lowercase())
+        .collect();
+    if words.is_empty() {
+        return None;
+    }
+
+    let shingles: HashSet<&[String]> = if words.len() < SHINGLE_SIZE {
+        std::iter::once(&words[..]).collect()
+    } else {
+        words.windows(SHINGLE_SIZE).collect()
+    };
+
+    let mut signature = [u64::MAX; MINHASH_SIZE];
+    for shingle in shingles {
+        for (seed, min) in signature.iter_mut().enumerate() {
+            let mut hasher = DefaultHasher::new();
+            seed.hash(&mut hasher);
+            shingle.hash(&mut hasher);
+            *min = (*min).min(hasher.finish());
+        }
+    }
+
+    Some(signature)
+}
@@ -0,0 +1,225 @@
+// Vault backup for patto-mobile
+// Export the workspace to a zip archive and restore it again
+
+use crate::commands::files::resolve_within_root;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Progress event for archive export and import
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveProgress {
+    pub processed: usize,
+    pub total: usize,
+    pub percent: u32,
+}
+
+/// Summary of an exported archive
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportResult {
+    pub file_count: usize,
+    pub total_bytes: u64,
+}
+
+/// Zip every note and attachment in the workspace into `dest_path`
+/// Hidden files and directories (`.git`, `.trash`, ...) are skipped.
+/// Emits `export-progress` events while writing.
+#[tauri::command]
+pub async fn export_vault_zip(
+    app: AppHandle,
+    root: PathBuf,
+    dest_path: PathBuf,
+) -> Result<ExportResult, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut files = Vec::new();
+        collect_vault_files(&root, &root, &mut files)
+            .map_err(|e| format!("Failed to scan workspace: {}", e))?;
+        // The archive itself may be written inside the workspace
+        files.retain(|path| root.join(path) != dest_path);
+
+        let dest =
+            File::create(&dest_path).map_err(|e| format!("Failed to create archive: {}", e))?;
+        let mut zip = ZipWriter::new(dest);
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        let total = files.len();
+        let mut total_bytes = 0;
+        let mut last_percent = 0;
+
+        for (i, relative_path) in files.iter().enumerate() {
+            // Zip entries always use forward slashes
+            let name = relative_path.to_string_lossy().replace('\\', "/");
+            zip.start_file(name, options)
+                .map_err(|e| format!("Failed to add {}: {}", relative_path.display(), e))?;
+
+            // Stream the file so large attachments aren't held in memory
+            let mut file = File::open(root.join(relative_path))
+                .map_err(|e| format!("Failed to read {}: {}", relative_path.display(), e))?;
+            total_bytes += io::copy(&mut file, &mut zip)
+                .map_err(|e| format!("Failed to write {}: {}", relative_path.display(), e))?;
+
+            // Only emit every 5% to reduce event spam
+            let percent = ((i + 1) * 100 / total) as u32;
+            if percent >= last_percent + 5 || percent == 100 {
+                last_percent = percent;
+                let _ = app.emit(
+                    "export-progress",
+                    ArchiveProgress {
+                        processed: i + 1,
+                        total,
+                        percent,
+                    },
+                );
+            }
+        }
+
+        zip.finish()
+            .map_err(|e| format!("Failed to finish archive: {}", e))?;
+
+        Ok(ExportResult {
+            file_count: total,
+            total_bytes,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Summary of an imported archive
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportResult {
+    pub file_count: usize,
+    pub total_bytes: u64,
+    /// Existing files that were replaced (only with `overwrite`)
+    pub overwritten: usize,
+}
+
+/// Extract a vault archive into `root`
+/// Fails without writing anything if files would be replaced, unless `overwrite` is set.
+/// Entries whose paths would escape `root` are rejected, and hidden entries (`.git`, ...)
+/// are skipped like on export. Emits `import-progress` events.
+#[tauri::command]
+pub async fn import_vault_zip(
+    app: AppHandle,
+    root: PathBuf,
+    zip_path: PathBuf,
+    overwrite: bool,
+) -> Result<ImportResult, String> {
+    tokio::task::spawn_blocking(move || {
+        let file = File::open(&zip_path).map_err(|e| format!("Failed to open archive: {}", e))?;
+        let mut archive =
+            ZipArchive::new(file).map_err(|e| format!("Failed to read archive: {}", e))?;
+
+        // Validate every entry before extracting any of them
+        let mut entries = Vec::new();
+        let mut existing = 0;
+        for i in 0..archive.len() {
+            let entry = archive
+                .by_index(i)
+                .map_err(|e| format!("Failed to read archive entry: {}", e))?;
+            if entry.is_dir() {
+                continue;
+            }
+
+            // Zip-slip protection: no absolute paths or `..` components
+            let relative_path = entry
+                .enclosed_name()
+                .ok_or_else(|| format!("Unsafe path in archive: {}", entry.name()))?;
+
+            // Never let an archive write into `.git` or other hidden state
+            if relative_path
+                .components()
+                .any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+            {
+                continue;
+            }
+
+            // Symlinks already in the workspace must not lead extraction outside it
+            let dest = resolve_within_root(&root, &relative_path.to_string_lossy())
+                .map_err(|_| format!("Unsafe path in archive: {}", entry.name()))?;
+            if dest.exists() {
+                existing += 1;
+            }
+            entries.push((i, relative_path, dest));
+        }
+
+        if existing > 0 && !overwrite {
+            return Err(format!(
+                "Conflict: {} files in the archive already exist in the workspace",
+                existing
+            ));
+        }
+
+        let total = entries.len();
+        let mut total_bytes = 0;
+        let mut last_percent = 0;
+
+        for (n, (i, relative_path, dest)) in entries.iter().enumerate() {
+            let mut entry = archive
+                .by_index(*i)
+                .map_err(|e| format!("Failed to read archive entry: {}", e))?;
+
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create directory: {}", e))?;
+            }
+
+            let mut out = File::create(dest)
+                .map_err(|e| format!("Failed to create {}: {}", relative_path.display(), e))?;
+            total_bytes += io::copy(&mut entry, &mut out)
+                .map_err(|e| format!("Failed to extract {}: {}", relative_path.display(), e))?;
+
+            // Only emit every 5% to reduce event spam
+            let percent = ((n + 1) * 100 / total) as u32;
+            if percent >= last_percent + 5 || percent == 100 {
+                last_percent = percent;
+                let _ = app.emit(
+                    "import-progress",
+                    ArchiveProgress {
+                        processed: n + 1,
+                        total,
+                        percent,
+                    },
+                );
+            }
+        }
+
+        Ok(ImportResult {
+            file_count: total,
+            total_bytes,
+            overwritten: existing,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Collect every non-hidden file under `dir` as paths relative to `root`
+fn collect_vault_files(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        // Skip hidden files and directories
+        if path
+            .file_name()
+            .map(|n| n.to_string_lossy().starts_with('.'))
+            .unwrap_or(false)
+        {
+            continue;
+        }
+
+        if path.is_dir() {
+            collect_vault_files(root, &path, files)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            files.push(relative.to_path_buf());
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,357 @@
+// Stored git credentials for patto-mobile
+// Keeps tokens in the platform keychain so the webview never has to hold them
+
+use crate::commands::git::GitCredentials;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+/// Keychain service name the credentials are filed under
+#[cfg(not(target_os = "android"))]
+const KEYRING_SERVICE: &str = "patto-mobile";
+
+/// What is stored for a host, without the secrets themselves
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredCredentialsInfo {
+    pub host: String,
+    pub username: String,
+    pub has_token: bool,
+    pub has_ssh_key: bool,
+}
+
+/// Save credentials for the host of `remote` (a URL or bare host name)
+#[tauri::command]
+pub fn store_credentials(
+    app: AppHandle,
+    remote: String,
+    credentials: GitCredentials,
+) -> Result<StoredCredentialsInfo, String> {
+    let host = remote_host(&remote).ok_or_else(|| format!("No host in {}", remote))?;
+    let json = serde_json::to_string(&credentials)
+        .map_err(|e| format!("Failed to serialize credentials: {}", e))?;
+    backend::write(&app, &host, &json)?;
+
+    Ok(credentials_info(host, &credentials))
+}
+
+/// Report which credentials are stored for the host of `remote`
+/// Secrets stay on the Rust side; only the username and what kinds are stored are returned.
+#[tauri::command]
+pub fn load_credentials(
+    app: AppHandle,
+    remote: String,
+) -> Result<Option<StoredCredentialsInfo>, String> {
+    let host = remote_host(&remote).ok_or_else(|| format!("No host in {}", remote))?;
+
+    Ok(stored_credentials(&app, &remote).map(|c| credentials_info(host, &c)))
+}
+
+/// Credentials to use for `remote_url`: the given ones, or the stored ones if those are empty
+pub fn resolve_credentials(
+    app: &AppHandle,
+    remote_url: &str,
+    credentials: GitCredentials,
+) -> GitCredentials {
+    if !credentials.is_empty() {
+        return credentials;
+    }
+
+    stored_credentials(app, remote_url).unwrap_or(credentials)
+}
+
+/// Stored credentials for the host of `remote`, if any
+fn stored_credentials(app: &AppHandle, remote: &str) -> Option<GitCredentials> {
+    let host = remote_host(remote)?;
+    match backend::read(app, &host) {
+        Ok(Some(json)) => serde_json::from_str(&json).ok(),
+        Ok(None) => None,
+        Err(e) => {
+            log::warn!("Failed to read credentials for {}: {}", host, e);
+            None
+        }
+    }
+}
+
+fn credentials_info(host: String, credentials: &GitCredentials) -> StoredCredentialsInfo {
+    StoredCredentialsInfo {
+        host,
+        username: credentials.username.clone(),
+        has_token: !credentials.token.is_empty(),
+        has_ssh_key: credentials.ssh_private_key.is_some(),
+    }
+}
+
+/// Host part of `https://host/repo`, `ssh://user@host:port/repo`, `git@host:repo` or a bare host
+fn remote_host(remote: &str) -> Option<String> {
+    let rest = match remote.split_once("://") {
+        Some((_, rest)) => rest.split('/').next()?,
+        // scp-like syntax, host ends at the first ':'
+        None => remote.split(':').next()?,
+    };
+    let host = rest.rsplit('@').next()?;
+    // Drop a port, keeping bracketed IPv6 addresses intact
+    let host = if host.starts_with('[') {
+        host.split_inclusive(']').next()?
+    } else {
+        host.split(':').next()?
+    };
+
+    (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
+
+/// Keychain on desktop and iOS
+#[cfg(not(target_os = "android"))]
+mod backend {
+    use super::KEYRING_SERVICE;
+    use tauri::AppHandle;
+
+    pub fn read(_app: &AppHandle, host: &str) -> Result<Option<String>, String> {
+        let entry = keyring::Entry::new(KEYRING_SERVICE, host)
+            .map_err(|e| format!("Failed to open keychain: {}", e))?;
+        match entry.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(format!("Failed to read keychain: {}", e)),
+        }
+    }
+
+    pub fn write(_app: &AppHandle, host: &str, secret: &str) -> Result<(), String> {
+        keyring::Entry::new(KEYRING_SERVICE, host)
+            .and_then(|entry| entry.set_password(secret))
+            .map_err(|e| format!("Failed to write keychain: {}", e))
+    }
+}
+
+/// Android: each host's secret is an AES-GCM encrypted file in the app data dir,
+/// with the key generated inside (and never leaving) the Android Keystore
+#[cfg(target_os = "android")]
+mod backend {
+    use jni::objects::{JByteArray, JObject, JValue};
+    use jni::JNIEnv;
+    use std::fs;
+    use std::path::PathBuf;
+    use tauri::{AppHandle, Manager};
+
+    /// Alias of the AES key in the Android Keystore
+    const KEY_ALIAS: &str = "patto-mobile-credentials";
+    /// `Cipher.ENCRYPT_MODE` / `Cipher.DECRYPT_MODE`
+    const ENCRYPT_MODE: i32 = 1;
+    const DECRYPT_MODE: i32 = 2;
+    /// `KeyProperties.PURPOSE_ENCRYPT | KeyProperties.PURPOSE_DECRYPT`
+    const PURPOSE_ENCRYPT_DECRYPT: i32 = 3;
+    const GCM_IV_LEN: usize = 12;
+    const GCM_TAG_BITS: i32 = 128;
+
+    pub fn read(app: &AppHandle, host: &str) -> Result<Option<String>, String> {
+        let path = secret_path(app, host)?;
+        let data = match fs::read(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Failed to read credentials: {}", e)),
+        };
+        if data.len() <= GCM_IV_LEN {
+            return Err(format!("Corrupt credentials file: {}", path.display()));
+        }
+
+        let (iv, ciphertext) = data.split_at(GCM_IV_LEN);
+        let plaintext = with_env(|env| decrypt(env, iv, ciphertext))?;
+        String::from_utf8(plaintext)
+            .map(Some)
+            .map_err(|e| format!("Failed to decode credentials: {}", e))
+    }
+
+    pub fn write(app: &AppHandle, host: &str, secret: &str) -> Result<(), String> {
+        let (iv, ciphertext) = with_env(|env| encrypt(env, secret.as_bytes()))?;
+
+        let path = secret_path(app, host)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create credentials dir: {}", e))?;
+        }
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, [iv, ciphertext].concat())
+            .and_then(|_| fs::rename(&temp_path, &path))
+            .map_err(|e| format!("Failed to write credentials: {}", e))
+    }
+
+    /// Host names may hold characters that aren't valid in file names, so hash them
+    fn secret_path(app: &AppHandle, host: &str) -> Result<PathBuf, String> {
+        let dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+        let name = blake3::hash(host.as_bytes()).to_hex();
+        Ok(dir.join("credentials").join(format!("{}.bin", name)))
+    }
+
+    /// Run `f` on the current thread's JNI env, inside a local frame so no references leak
+    fn with_env<T>(f: impl FnOnce(&mut JNIEnv) -> jni::errors::Result<T>) -> Result<T, String> {
+        let context = ndk_context::android_context();
+        // SAFETY: the VM pointer comes from the Android activity and outlives the app
+        let vm = unsafe { jni::JavaVM::from_raw(context.vm().cast()) }
+            .map_err(|e| format!("Failed to get Java VM: {}", e))?;
+        let mut env = vm
+            .attach_current_thread()
+            .map_err(|e| format!("Failed to attach to Java VM: {}", e))?;
+
+        let result = env.with_local_frame(16, |env| f(env));
+        if env.exception_check().unwrap_or(false) {
+            let _ = env.exception_clear();
+        }
+        result.map_err(|e| format!("Android Keystore error: {}", e))
+    }
+
+    fn encrypt(env: &mut JNIEnv, plaintext: &[u8]) -> jni::errors::Result<(Vec<u8>, Vec<u8>)> {
+        let key = secret_key(env)?;
+        let cipher = cipher(env)?;
+        env.call_method(
+            &cipher,
+            "init",
+            "(ILjava/security/Key;)V",
+            &[JValue::Int(ENCRYPT_MODE), JValue::Object(&key)],
+        )?;
+
+        let iv = env.call_method(&cipher, "getIV", "()[B", &[])?.l()?;
+        let iv = env.convert_byte_array(JByteArray::from(iv))?;
+        let ciphertext = do_final(env, &cipher, plaintext)?;
+        Ok((iv, ciphertext))
+    }
+
+    fn decrypt(env: &mut JNIEnv, iv: &[u8], ciphertext: &[u8]) -> jni::errors::Result<Vec<u8>> {
+        let key = secret_key(env)?;
+        let cipher = cipher(env)?;
+        let iv = env.byte_array_from_slice(iv)?;
+        let spec = env.new_object(
+            "javax/crypto/spec/GCMParameterSpec",
+            "(I[B)V",
+            &[JValue::Int(GCM_TAG_BITS), JValue::Object(&iv)],
+        )?;
+        env.call_method(
+            &cipher,
+            "init",
+            "(ILjava/security/Key;Ljava/security/spec/AlgorithmParameterSpec;)V",
+            &[
+                JValue::Int(DECRYPT_MODE),
+                JValue::Object(&key),
+                JValue::Object(&spec),
+            ],
+        )?;
+
+        do_final(env, &cipher, ciphertext)
+    }
+
+    fn cipher<'local>(env: &mut JNIEnv<'local>) -> jni::errors::Result<JObject<'local>> {
+        let transformation = env.new_string("AES/GCM/NoPadding")?;
+        env.call_static_method(
+            "javax/crypto/Cipher",
+            "getInstance",
+            "(Ljava/lang/String;)Ljavax/crypto/Cipher;",
+            &[JValue::Object(&transformation)],
+        )?
+        .l()
+    }
+
+    fn do_final(env: &mut JNIEnv, cipher: &JObject, input: &[u8]) -> jni::errors::Result<Vec<u8>> {
+        let input = env.byte_array_from_slice(input)?;
+        let output = env
+            .call_method(cipher, "doFinal", "([B)[B", &[JValue::Object(&input)])?
+            .l()?;
+        env.convert_byte_array(JByteArray::from(output))
+    }
+
+    /// The Keystore AES key, generated on first use
+    fn secret_key<'local>(env: &mut JNIEnv<'local>) -> jni::errors::Result<JObject<'local>> {
+        let provider = env.new_string("AndroidKeyStore")?;
+        let alias = env.new_string(KEY_ALIAS)?;
+
+        let key_store = env
+            .call_static_method(
+                "java/security/KeyStore",
+                "getInstance",
+                "(Ljava/lang/String;)Ljava/security/KeyStore;",
+                &[JValue::Object(&provider)],
+            )?
+            .l()?;
+        env.call_method(
+            &key_store,
+            "load",
+            "(Ljava/security/KeyStore$LoadStoreParameter;)V",
+            &[JValue::Object(&JObject::null())],
+        )?;
+        let key = env
+            .call_method(
+                &key_store,
+                "getKey",
+                "(Ljava/lang/String;[C)Ljava/security/Key;",
+                &[JValue::Object(&alias), JValue::Object(&JObject::null())],
+            )?
+            .l()?;
+        if !key.is_null() {
+            return Ok(key);
+        }
+
+        let builder_class = "android/security/keystore/KeyGenParameterSpec$Builder";
+        let builder_sig =
+            "([Ljava/lang/String;)Landroid/security/keystore/KeyGenParameterSpec$Builder;";
+        let block_modes = string_array(env, "GCM")?;
+        let paddings = string_array(env, "NoPadding")?;
+
+        let builder = env.new_object(
+            builder_class,
+            "(Ljava/lang/String;I)V",
+            &[JValue::Object(&alias), JValue::Int(PURPOSE_ENCRYPT_DECRYPT)],
+        )?;
+        env.call_method(
+            &builder,
+            "setBlockModes",
+            builder_sig,
+            &[JValue::Object(&block_modes)],
+        )?;
+        env.call_method(
+            &builder,
+            "setEncryptionPaddings",
+            builder_sig,
+            &[JValue::Object(&paddings)],
+        )?;
+        env.call_method(
+            &builder,
+            "setKeySize",
+            "(I)Landroid/security/keystore/KeyGenParameterSpec$Builder;",
+            &[JValue::Int(256)],
+        )?;
+        let spec = env
+            .call_method(
+                &builder,
+                "build",
+                "()Landroid/security/keystore/KeyGenParameterSpec;",
+                &[],
+            )?
+            .l()?;
+
+        let algorithm = env.new_string("AES")?;
+        let generator = env
+            .call_static_method(
+                "javax/crypto/KeyGenerator",
+                "getInstance",
+                "(Ljava/lang/String;Ljava/lang/String;)Ljavax/crypto/KeyGenerator;",
+                &[JValue::Object(&algorithm), JValue::Object(&provider)],
+            )?
+            .l()?;
+        env.call_method(
+            &generator,
+            "init",
+            "(Ljava/security/spec/AlgorithmParameterSpec;)V",
+            &[JValue::Object(&spec)],
+        )?;
+        env.call_method(&generator, "generateKey", "()Ljavax/crypto/SecretKey;", &[])?
+            .l()
+    }
+
+    fn string_array<'local>(
+        env: &mut JNIEnv<'local>,
+        value: &str,
+    ) -> jni::errors::Result<JObject<'local>> {
+        let value = env.new_string(value)?;
+        Ok(env.new_object_array(1, "java/lang/String", &value)?.into())
+    }
+}
@@ -1,9 +1,20 @@
 // File listing and metadata for patto-mobile
 
+use crate::commands::notes::{check_note_size, content_hash, extract_links_from_ast};
+use crate::commands::tasks::{
+    collect_patto_files as collect_note_paths, gitignore_repo, is_gitignored, task_counts,
+    TaskCache,
+};
+use git2::Repository;
+use patto::parser;
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::{Path, PathBuf};
-use std::time::UNIX_EPOCH;
+use std::path::{Component, Path, PathBuf};
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, State};
 
 /// Sort options for file listing
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -26,49 +37,142 @@ pub struct FileEntry {
     pub created_time: u64,
     pub backlink_count: u32,
     pub size_bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Only filled when requested via `list_files(include_task_counts)`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub open_task_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub done_task_count: Option<usize>,
+    /// Only filled by `get_file_info`, the file scan doesn't read contents
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+}
+
+/// Page of `list_files` results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileList {
+    pub entries: Vec<FileEntry>,
+    /// Number of matching files before pagination
+    pub total: usize,
 }
 
 /// List all patto files in a directory with sorting
+/// With `include_task_counts`, the returned notes get open/done task counts (`None` if `.pattoignore`d).
+/// With `respect_gitignore`, notes ignored by the workspace repository's `.gitignore` are left out.
+/// With `subdir`, only that directory is scanned; paths stay relative to `root`.
+/// With `extensions`, files with any of them are listed instead of only `.pn`.
+/// With `offset` and `limit`, only that slice of the sorted list is returned.
 #[tauri::command]
-pub fn list_files(root: PathBuf, sort_by: SortBy) -> Result<Vec<FileEntry>, String> {
-    let mut entries = collect_patto_files(&root).map_err(|e| e.to_string())?;
+pub async fn list_files(
+    app: AppHandle,
+    root: PathBuf,
+    sort_by: SortBy,
+    include_task_counts: Option<bool>,
+    respect_gitignore: Option<bool>,
+    subdir: Option<String>,
+    extensions: Option<Vec<String>>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<FileList, String> {
+    let scan_dir = match &subdir {
+        Some(subdir) => resolve_within_root(&root, subdir)?,
+        None => root.clone(),
+    };
+    let extensions: Vec<String> = match extensions {
+        Some(extensions) => extensions
+            .iter()
+            .map(|e| e.trim_start_matches('.').to_lowercase())
+            .collect(),
+        None => vec!["pn".to_string()],
+    };
 
-    // Sort based on criteria
-    match sort_by {
-        SortBy::LastModified => {
-            entries.sort_by(|a, b| b.modified_time.cmp(&a.modified_time));
-        }
-        SortBy::LastCreated => {
-            entries.sort_by(|a, b| b.created_time.cmp(&a.created_time));
+    tokio::task::spawn_blocking(move || {
+        let gitignore = gitignore_repo(&root, respect_gitignore.unwrap_or(false));
+        let mut entries =
+            collect_patto_files_filtered(&root, &scan_dir, &extensions, gitignore.as_ref())
+                .map_err(|e| e.to_string())?;
+
+        let backlinks = app.state::<LinkCache>().backlink_counts(&root)?;
+        for entry in &mut entries {
+            entry.backlink_count = backlinks.get(&entry.path).copied().unwrap_or(0);
         }
-        SortBy::MostLinked => {
-            entries.sort_by(|a, b| b.backlink_count.cmp(&a.backlink_count));
+
+        // Sort based on criteria
+        match sort_by {
+            SortBy::LastModified => {
+                entries.sort_by(|a, b| b.modified_time.cmp(&a.modified_time));
+            }
+            SortBy::LastCreated => {
+                entries.sort_by(|a, b| b.created_time.cmp(&a.created_time));
+            }
+            SortBy::MostLinked => {
+                entries.sort_by(|a, b| b.backlink_count.cmp(&a.backlink_count));
+            }
+            SortBy::Alphabetical => {
+                entries.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+            }
         }
-        SortBy::Alphabetical => {
-            entries.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+
+        // Paginate after sorting so pages stay stable
+        let total = entries.len();
+        let mut entries: Vec<FileEntry> = entries
+            .into_iter()
+            .skip(offset.unwrap_or(0))
+            .take(limit.unwrap_or(usize::MAX))
+            .collect();
+
+        // Only the returned page is counted, unchanged notes come from the task cache
+        if include_task_counts.unwrap_or(false) {
+            let paths: Vec<String> = entries.iter().map(|e| e.path.clone()).collect();
+            let counts = task_counts(&root, &paths, Some(app.state::<TaskCache>().inner()))?;
+            for (entry, counts) in entries.iter_mut().zip(counts) {
+                entry.open_task_count = counts.map(|(open, _)| open);
+                entry.done_task_count = counts.map(|(_, done)| done);
+            }
         }
-    }
 
-    Ok(entries)
+        Ok(FileList { entries, total })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
 }
 
 /// Collect patto files recursively
-fn collect_patto_files(root: &Path) -> std::io::Result<Vec<FileEntry>> {
-    let mut entries = Vec::new();
+pub(crate) fn collect_patto_files(root: &Path) -> std::io::Result<Vec<FileEntry>> {
+    collect_patto_files_filtered(root, root, &["pn".to_string()], None)
+}
 
-    if !root.is_dir() {
-        return Ok(entries);
+/// Collect files with one of `extensions` (lowercase, no dot) under `dir`,
+/// skipping those ignored by `gitignore`
+fn collect_patto_files_filtered(
+    root: &Path,
+    dir: &Path,
+    extensions: &[String],
+    gitignore: Option<&Repository>,
+) -> std::io::Result<Vec<FileEntry>> {
+    let mut paths = Vec::new();
+
+    if !dir.is_dir() {
+        return Ok(Vec::new());
     }
 
-    collect_patto_files_recursive(root, root, &mut entries)?;
+    // Walk serially, then stat the files on the thread pool
+    collect_patto_files_recursive(root, dir, extensions, gitignore, &mut paths)?;
 
-    Ok(entries)
+    paths
+        .par_iter()
+        .map(|path| scanned_file_entry(root, path))
+        .collect()
 }
 
 fn collect_patto_files_recursive(
     root: &Path,
     dir: &Path,
-    entries: &mut Vec<FileEntry>,
+    extensions: &[String],
+    gitignore: Option<&Repository>,
+    paths: &mut Vec<PathBuf>,
 ) -> std::io::Result<()> {
     for entry in fs::read_dir(dir)? {
         let entry = entry?;
@@ -83,56 +187,321 @@ fn collect_patto_files_recursive(
             continue;
         }
 
+        if is_gitignored(gitignore, path.strip_prefix(root).unwrap_or(&path)) {
+            continue;
+        }
+
+        if path.is_dir() {
+            collect_patto_files_recursive(root, &path, extensions, gitignore, paths)?;
+        } else if path
+            .extension()
+            .map(|e| extensions.contains(&e.to_string_lossy().to_lowercase()))
+            .unwrap_or(false)
+        {
+            paths.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Build the file list entry for a file found by the scan
+fn scanned_file_entry(root: &Path, path: &Path) -> std::io::Result<FileEntry> {
+    // Get file metadata
+    let metadata = fs::metadata(path)?;
+
+    let modified_time = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let created_time = metadata
+        .created()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    // Get relative path from root
+    let relative_path = path
+        .strip_prefix(root)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.to_string_lossy().to_string());
+
+    // Get file name without extension
+    let name = path
+        .file_stem()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    Ok(FileEntry {
+        path: relative_path,
+        name,
+        modified_time,
+        created_time,
+        backlink_count: 0, // Filled from the link cache by `list_files`
+        size_bytes: metadata.len(),
+        error: None,
+        open_task_count: None,
+        done_task_count: None,
+        content_hash: None,
+    })
+}
+
+/// Node of the workspace directory tree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeNode {
+    /// File name without `.pn`, or the directory name
+    pub name: String,
+    /// Path relative to the workspace root
+    pub path: String,
+    pub is_dir: bool,
+    pub children: Vec<TreeNode>,
+}
+
+/// List patto files as a directory tree, directories first and then alphabetically
+/// Directories without any notes (directly or in subdirectories) are left out.
+#[tauri::command]
+pub fn list_tree(root: PathBuf) -> Result<Vec<TreeNode>, String> {
+    if !root.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    collect_tree(&root, &root).map_err(|e| e.to_string())
+}
+
+fn collect_tree(root: &Path, dir: &Path) -> std::io::Result<Vec<TreeNode>> {
+    let mut nodes = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        // Skip hidden files and directories
+        if path
+            .file_name()
+            .map(|n| n.to_string_lossy().starts_with('.'))
+            .unwrap_or(false)
+        {
+            continue;
+        }
+
+        let relative_path = path
+            .strip_prefix(root)
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|_| path.to_string_lossy().to_string());
+
         if path.is_dir() {
-            collect_patto_files_recursive(root, &path, entries)?;
+            let children = collect_tree(root, &path)?;
+            if children.is_empty() {
+                continue;
+            }
+            nodes.push(TreeNode {
+                name: path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                path: relative_path,
+                is_dir: true,
+                children,
+            });
         } else if path.extension().map(|e| e == "pn").unwrap_or(false) {
-            // Get file metadata
-            let metadata = fs::metadata(&path)?;
-
-            let modified_time = metadata
-                .modified()
-                .ok()
-                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
-                .map(|d| d.as_secs())
-                .unwrap_or(0);
-
-            let created_time = metadata
-                .created()
-                .ok()
-                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
-                .map(|d| d.as_secs())
-                .unwrap_or(0);
-
-            // Get relative path from root
-            let relative_path = path
-                .strip_prefix(root)
-                .map(|p| p.to_string_lossy().to_string())
-                .unwrap_or_else(|_| path.to_string_lossy().to_string());
-
-            // Get file name without extension
-            let name = path
-                .file_stem()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_default();
-
-            entries.push(FileEntry {
+            nodes.push(TreeNode {
+                name: path
+                    .file_stem()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default(),
                 path: relative_path,
-                name,
-                modified_time,
-                created_time,
-                backlink_count: 0, // Will be populated when repository is initialized
-                size_bytes: metadata.len(),
+                is_dir: false,
+                children: Vec::new(),
             });
         }
     }
 
-    Ok(())
+    nodes.sort_by(|a, b| {
+        b.is_dir
+            .cmp(&a.is_dir)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+
+    Ok(nodes)
+}
+
+/// Note link targets parsed from a file, valid while its mtime is unchanged
+struct CachedLinks {
+    modified: SystemTime,
+    targets: HashSet<String>,
+}
+
+/// Cached outbound links plus the inbound counts they add up to
+#[derive(Default)]
+struct LinkIndex {
+    files: HashMap<PathBuf, CachedLinks>,
+    /// Inbound counts over `files`, kept in step as entries are added and removed
+    inbound: HashMap<String, u32>,
+    /// Workspace of the last full scan, `None` until one has run (or after `clear`)
+    scanned_root: Option<PathBuf>,
+    /// Files invalidated since, re-parsed before `inbound` is read again
+    dirty: HashSet<PathBuf>,
+}
+
+impl LinkIndex {
+    fn insert(&mut self, full_path: PathBuf, links: CachedLinks) {
+        self.remove(&full_path);
+        for target in &links.targets {
+            *self.inbound.entry(target.clone()).or_default() += 1;
+        }
+        self.files.insert(full_path, links);
+    }
+
+    fn remove(&mut self, full_path: &Path) {
+        let Some(old) = self.files.remove(full_path) else {
+            return;
+        };
+        for target in old.targets {
+            if let Some(count) = self.inbound.get_mut(&target) {
+                *count -= 1;
+                if *count == 0 {
+                    self.inbound.remove(&target);
+                }
+            }
+        }
+    }
+}
+
+/// Per-file cache of outbound note links, used to count backlinks without re-parsing every note
+/// `write_note` invalidates the written file since mtimes can be too coarse to notice quick edits.
+#[derive(Default)]
+pub struct LinkCache {
+    index: RwLock<LinkIndex>,
+}
+
+impl LinkCache {
+    /// Inbound wikilink counts keyed by relative note path
+    /// Each linking note counts once, and self-links are ignored
+    pub(crate) fn backlink_counts(&self, root: &Path) -> Result<HashMap<String, u32>, String> {
+        let files = collect_note_paths(root).map_err(|e| e.to_string())?;
+        // Cache misses are parsed on the thread pool
+        let outbound: Vec<HashSet<String>> = files
+            .par_iter()
+            .map(|file_path| self.outbound_links(root, file_path))
+            .collect();
+
+        let mut counts = HashMap::new();
+        for target in outbound.into_iter().flatten() {
+            *counts.entry(target).or_default() += 1;
+        }
+
+        // Remember the result so single-file lookups don't have to scan again
+        let scanned: HashSet<PathBuf> = files.iter().map(|f| root.join(f)).collect();
+        let mut index = self.index.write().unwrap();
+        index.files.retain(|path, _| scanned.contains(path));
+        index.inbound = counts.clone();
+        index.scanned_root = Some(root.to_path_buf());
+
+        Ok(counts)
+    }
+
+    /// Inbound wikilink count of one note
+    /// Reuses the last full scan, re-parsing only the notes invalidated since then
+    pub(crate) fn backlink_count(&self, root: &Path, file_path: &str) -> Result<u32, String> {
+        let dirty = {
+            let mut index = self.index.write().unwrap();
+            if index.scanned_root.as_deref() != Some(root) {
+                drop(index);
+                return Ok(self
+                    .backlink_counts(root)?
+                    .get(file_path)
+                    .copied()
+                    .unwrap_or(0));
+            }
+            std::mem::take(&mut index.dirty)
+        };
+
+        for full_path in dirty {
+            if let Ok(relative) = full_path.strip_prefix(root) {
+                self.outbound_links(root, &relative.to_string_lossy());
+            }
+        }
+
+        let index = self.index.read().unwrap();
+        Ok(index.inbound.get(file_path).copied().unwrap_or(0))
+    }
+
+    /// Relative paths of the notes `file_path` links to
+    pub(crate) fn outbound_links(&self, root: &Path, file_path: &str) -> HashSet<String> {
+        let full_path = root.join(file_path);
+        let modified = fs::metadata(&full_path).and_then(|m| m.modified()).ok();
+
+        if let Some(modified) = modified {
+            let index = self.index.read().unwrap();
+            if let Some(cached) = index
+                .files
+                .get(&full_path)
+                .filter(|c| c.modified == modified)
+            {
+                return cached.targets.clone();
+            }
+        }
+
+        // Pathologically large files are treated as having no links
+        let mut links = Vec::new();
+        if check_note_size(&full_path, file_path, None).is_ok() {
+            if let Ok(content) = fs::read_to_string(&full_path) {
+                extract_links_from_ast(&parser::parse_text(&content).ast, &mut links);
+            }
+        }
+        let targets: HashSet<String> = links
+            .into_iter()
+            .filter(|link| !link.is_external && !link.target.is_empty())
+            .map(|link| format!("{}.pn", link.target))
+            .filter(|target| target != file_path)
+            .collect();
+
+        if let Some(modified) = modified {
+            self.index.write().unwrap().insert(
+                full_path,
+                CachedLinks {
+                    modified,
+                    targets: targets.clone(),
+                },
+            );
+        }
+
+        targets
+    }
+
+    /// Forget the cached links of one file
+    pub(crate) fn invalidate(&self, full_path: &Path) {
+        let mut index = self.index.write().unwrap();
+        index.remove(full_path);
+        index.dirty.insert(full_path.to_path_buf());
+    }
+
+    /// Drop every cached entry
+    pub(crate) fn clear(&self) {
+        *self.index.write().unwrap() = LinkIndex::default();
+    }
 }
 
 /// Get file details for a specific file
 #[tauri::command]
-pub fn get_file_info(root: PathBuf, file_path: String) -> Result<FileEntry, String> {
-    let full_path = root.join(&file_path);
+pub fn get_file_info(
+    links: State<'_, LinkCache>,
+    root: PathBuf,
+    file_path: String,
+) -> Result<FileEntry, String> {
+    let mut entry = file_info(root.clone(), file_path)?;
+    entry.backlink_count = links.backlink_count(&root, &entry.path)?;
+
+    Ok(entry)
+}
+
+/// File details without the backlink count, which needs a workspace scan
+fn file_info(root: PathBuf, file_path: String) -> Result<FileEntry, String> {
+    let full_path = resolve_within_root(&root, &file_path)?;
 
     if !full_path.exists() {
         return Err(format!("File not found: {}", file_path));
@@ -166,12 +535,59 @@ pub fn get_file_info(root: PathBuf, file_path: String) -> Result<FileEntry, Stri
         created_time,
         backlink_count: 0,
         size_bytes: metadata.len(),
+        error: None,
+        open_task_count: None,
+        done_task_count: None,
+        content_hash: fs::read(&full_path).ok().map(|bytes| content_hash(&bytes)),
     })
 }
 
+/// Get file details for several files in one call
+/// Missing or unreadable files get an entry with `error` set instead of failing the batch
+#[tauri::command]
+pub fn get_file_info_batch(
+    links: State<'_, LinkCache>,
+    root: PathBuf,
+    file_paths: Vec<String>,
+) -> Result<Vec<FileEntry>, String> {
+    Ok(file_paths
+        .into_iter()
+        .map(|file_path| {
+            let entry = file_info(root.clone(), file_path.clone()).and_then(|mut entry| {
+                entry.backlink_count = links.backlink_count(&root, &entry.path)?;
+                Ok(entry)
+            });
+            entry.unwrap_or_else(|e| FileEntry {
+                name: Path::new(&file_path)
+                    .file_stem()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                path: file_path,
+                modified_time: 0,
+                created_time: 0,
+                backlink_count: 0,
+                size_bytes: 0,
+                error: Some(e),
+                open_task_count: None,
+                done_task_count: None,
+                content_hash: None,
+            })
+        })
+        .collect())
+}
+
+/// Hidden directory holding note templates (`<name>.pn`)
+const TEMPLATES_DIR: &str = ".templates";
+
 /// Create a new patto file
+/// With `template`, the file starts with `.templates/<template>.pn`, where
+/// `{{title}}`, `{{date}}` and `{{time}}` are replaced
 #[tauri::command]
-pub fn create_file(root: PathBuf, name: String) -> Result<FileEntry, String> {
+pub fn create_file(
+    root: PathBuf,
+    name: String,
+    template: Option<String>,
+) -> Result<FileEntry, String> {
     // Sanitize name and add extension
     let file_name = if name.ends_with(".pn") {
         name
@@ -179,35 +595,301 @@ pub fn create_file(root: PathBuf, name: String) -> Result<FileEntry, String> {
         format!("{}.pn", name)
     };
 
-    let full_path = root.join(&file_name);
+    let full_path = resolve_within_root(&root, &file_name)?;
 
     // Check if file already exists
     if full_path.exists() {
         return Err(format!("File already exists: {}", file_name));
     }
 
-    // Create empty file
-    fs::write(&full_path, "").map_err(|e| format!("Failed to create file: {}", e))?;
+    let content = match template {
+        Some(template) => render_template(&root, &template, &full_path)?,
+        None => String::new(),
+    };
+
+    fs::write(&full_path, content).map_err(|e| format!("Failed to create file: {}", e))?;
+
+    file_info(root, file_name)
+}
+
+/// Read a template and fill in its placeholders for the note at `note_path`
+fn render_template(root: &Path, template: &str, note_path: &Path) -> Result<String, String> {
+    let template_name = template.strip_suffix(".pn").unwrap_or(template);
+    if !is_plain_relative(Path::new(template_name)) {
+        return Err(format!("Invalid template name: {}", template));
+    }
+
+    let template_path = root
+        .join(TEMPLATES_DIR)
+        .join(format!("{}.pn", template_name));
+    let content = fs::read_to_string(&template_path)
+        .map_err(|e| format!("Failed to read template {}: {}", template_name, e))?;
+
+    let now = chrono::Local::now();
+    let title = note_path
+        .file_stem()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    Ok(content
+        .replace("{{title}}", &title)
+        .replace("{{date}}", &now.format("%Y-%m-%d").to_string())
+        .replace("{{time}}", &now.format("%H:%M").to_string()))
+}
+
+/// Copy a patto file to `dest_name` (relative to root, `.pn` optional)
+/// If that name is taken a numeric suffix is added, e.g. `note-2.pn`
+#[tauri::command]
+pub fn copy_file(
+    root: PathBuf,
+    source_path: String,
+    dest_name: String,
+) -> Result<FileEntry, String> {
+    let source_full_path = resolve_within_root(&root, &source_path)?;
+
+    if !source_full_path.is_file() {
+        return Err(format!("File not found: {}", source_path));
+    }
+
+    let stem = dest_name.strip_suffix(".pn").unwrap_or(&dest_name);
+    if !is_plain_relative(Path::new(stem)) {
+        return Err(format!("Path is outside the workspace: {}", dest_name));
+    }
+
+    let mut file_name = format!("{}.pn", stem);
+    let mut suffix = 2;
+    while root.join(&file_name).exists() {
+        file_name = format!("{}-{}.pn", stem, suffix);
+        suffix += 1;
+    }
 
-    get_file_info(root, file_name)
+    let dest_full_path = resolve_within_root(&root, &file_name)?;
+    if let Some(parent) = dest_full_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    fs::copy(&source_full_path, &dest_full_path)
+        .map_err(|e| format!("Failed to copy file: {}", e))?;
+
+    file_info(root, file_name)
 }
 
-/// Delete a patto file
+/// Delete a patto file by moving it to the trash
+/// Use `restore_file` to bring it back or `empty_trash` to remove it for good
 #[tauri::command]
 pub fn delete_file(root: PathBuf, file_path: String) -> Result<(), String> {
-    let full_path = root.join(&file_path);
+    let full_path = resolve_within_root(&root, &file_path)?;
 
     if !full_path.exists() {
         return Err(format!("File not found: {}", file_path));
     }
 
-    fs::remove_file(&full_path).map_err(|e| format!("Failed to delete file: {}", e))
+    move_to_trash(&root, &file_path).map(|_| ())
+}
+
+/// Hidden directory deleted notes are moved into (skipped by the file scan)
+pub(crate) const TRASH_DIR: &str = ".trash";
+
+/// Directory inside `.trash/` mapping each trashed file to its original path
+const TRASH_ORIGINS_DIR: &str = ".origins";
+
+/// Move a file into `.trash/`, keeping its relative path
+/// A timestamp and counter are added if the trash already holds a file with that
+/// name; the original path is then recorded under `.trash/.origins/`
+pub(crate) fn move_to_trash(root: &Path, file_path: &str) -> Result<PathBuf, String> {
+    let full_path = resolve_within_root(root, file_path)?;
+    let trash = root.join(TRASH_DIR);
+    let mut trash_path = trash.join(file_path);
+
+    if trash_path.exists() {
+        let timestamp = chrono::Local::now().format("%Y%m%d%H%M%S");
+        let stem = trash_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let extension = trash_path
+            .extension()
+            .map(|ext| format!(".{}", ext.to_string_lossy()))
+            .unwrap_or_default();
+
+        let mut counter = 1;
+        loop {
+            trash_path.set_file_name(format!("{}.{}-{}{}", stem, timestamp, counter, extension));
+            if !trash_path.exists() {
+                break;
+            }
+            counter += 1;
+        }
+    }
+
+    if let Some(parent) = trash_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    fs::rename(&full_path, &trash_path).map_err(|e| format!("Failed to move to trash: {}", e))?;
+
+    if let Ok(relative) = trash_path.strip_prefix(&trash) {
+        if relative != Path::new(file_path) {
+            let origin = trash.join(TRASH_ORIGINS_DIR).join(relative);
+            if let Some(parent) = origin.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create directory: {}", e))?;
+            }
+            fs::write(&origin, file_path)
+                .map_err(|e| format!("Failed to record trash origin: {}", e))?;
+        }
+    }
+
+    Ok(trash_path)
+}
+
+/// File in the trash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashEntry {
+    /// Path inside `.trash/`, passed to `restore_file`
+    pub path: String,
+    /// Where the file is restored to, without the collision suffix
+    pub original_path: String,
+    pub size_bytes: u64,
+}
+
+/// List the files in the trash
+#[tauri::command]
+pub fn list_trash(root: PathBuf) -> Result<Vec<TrashEntry>, String> {
+    let trash = root.join(TRASH_DIR);
+    let mut entries = Vec::new();
+
+    if trash.is_dir() {
+        collect_trash_entries(&trash, &trash, &mut entries)
+            .map_err(|e| format!("Failed to read trash: {}", e))?;
+    }
+
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(entries)
+}
+
+fn collect_trash_entries(
+    trash: &Path,
+    dir: &Path,
+    entries: &mut Vec<TrashEntry>,
+) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path == trash.join(TRASH_ORIGINS_DIR) {
+            continue;
+        } else if path.is_dir() {
+            collect_trash_entries(trash, &path, entries)?;
+        } else if let Ok(relative) = path.strip_prefix(trash) {
+            entries.push(TrashEntry {
+                path: relative.to_string_lossy().to_string(),
+                original_path: original_trash_path(trash, relative)
+                    .to_string_lossy()
+                    .to_string(),
+                size_bytes: fs::metadata(&path)?.len(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Original path of a trashed file, as recorded by `move_to_trash`
+/// Files trashed without a collision keep their path, so they have no record
+fn original_trash_path(trash: &Path, trash_path: &Path) -> PathBuf {
+    fs::read_to_string(trash.join(TRASH_ORIGINS_DIR).join(trash_path))
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| trash_path.to_path_buf())
+}
+
+/// Move a file from the trash back to its original location
+/// Fails if a file already exists there
+#[tauri::command]
+pub fn restore_file(root: PathBuf, trash_path: String) -> Result<FileEntry, String> {
+    if !is_plain_relative(Path::new(&trash_path))
+        || Path::new(&trash_path).starts_with(TRASH_ORIGINS_DIR)
+    {
+        return Err(format!("Path is outside the trash: {}", trash_path));
+    }
+
+    let trash = root.join(TRASH_DIR);
+    let full_trash_path = trash.join(&trash_path);
+    if !full_trash_path.is_file() {
+        return Err(format!("File not found in trash: {}", trash_path));
+    }
+
+    let original = original_trash_path(&trash, Path::new(&trash_path));
+    let full_path = resolve_within_root(&root, &original.to_string_lossy())?;
+    if full_path.exists() {
+        return Err(format!("File already exists: {}", original.display()));
+    }
+
+    if let Some(parent) = full_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    fs::rename(&full_trash_path, &full_path).map_err(|e| format!("Failed to restore: {}", e))?;
+    let _ = fs::remove_file(trash.join(TRASH_ORIGINS_DIR).join(&trash_path));
+
+    file_info(root, original.to_string_lossy().to_string())
+}
+
+/// Permanently delete everything in the trash, returning the number of files removed
+#[tauri::command]
+pub fn empty_trash(root: PathBuf) -> Result<usize, String> {
+    let removed = list_trash(root.clone())?.len();
+    let trash = root.join(TRASH_DIR);
+
+    if trash.is_dir() {
+        fs::remove_dir_all(&trash).map_err(|e| format!("Failed to empty trash: {}", e))?;
+    }
+
+    Ok(removed)
+}
+
+/// Only normal components, so joining onto a root can't escape it
+fn is_plain_relative(path: &Path) -> bool {
+    path.components()
+        .all(|c| matches!(c, std::path::Component::Normal(_)))
+}
+
+/// Join `relative` onto `root`, refusing paths that end up outside the workspace
+/// Rejects `..`, absolute paths, and symlinks that point out of `root`.
+pub(crate) fn resolve_within_root(root: &Path, relative: &str) -> Result<PathBuf, String> {
+    let path = Path::new(relative);
+    let outside = || format!("Path is outside the workspace: {}", relative);
+
+    if !path
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(outside());
+    }
+
+    let canonical_root = root
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve workspace: {}", e))?;
+    let full_path = root.join(path);
+
+    // The target may not exist yet, so check its deepest existing ancestor.
+    // A dangling symlink fails to canonicalize and is rejected too.
+    let inside = full_path
+        .ancestors()
+        .find(|p| fs::symlink_metadata(p).is_ok())
+        .and_then(|p| p.canonicalize().ok())
+        .is_some_and(|p| p.starts_with(&canonical_root));
+    if !inside {
+        return Err(outside());
+    }
+
+    Ok(full_path)
 }
 
 /// Rename a patto file
 #[tauri::command]
 pub fn rename_file(root: PathBuf, old_path: String, new_name: String) -> Result<FileEntry, String> {
-    let old_full_path = root.join(&old_path);
+    let old_full_path = resolve_within_root(&root, &old_path)?;
 
     if !old_full_path.exists() {
         return Err(format!("File not found: {}", old_path));
@@ -220,8 +902,11 @@ pub fn rename_file(root: PathBuf, old_path: String, new_name: String) -> Result<
         format!("{}.pn", new_name)
     };
 
-    let parent = old_full_path.parent().unwrap_or(&root);
-    let new_full_path = parent.join(&new_file_name);
+    let new_relative_path = Path::new(&old_path)
+        .with_file_name(&new_file_name)
+        .to_string_lossy()
+        .to_string();
+    let new_full_path = resolve_within_root(&root, &new_relative_path)?;
 
     if new_full_path.exists() {
         return Err(format!("File already exists: {}", new_file_name));
@@ -229,10 +914,270 @@ pub fn rename_file(root: PathBuf, old_path: String, new_name: String) -> Result<
 
     fs::rename(&old_full_path, &new_full_path).map_err(|e| format!("Failed to rename: {}", e))?;
 
-    let new_relative_path = new_full_path
-        .strip_prefix(&root)
-        .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or_else(|_| new_file_name);
+    file_info(root, new_relative_path)
+}
+
+/// Move a patto file to another relative path, possibly in a different directory
+/// Missing parent directories are created. `new_path` must stay inside `root`.
+#[tauri::command]
+pub fn move_file(root: PathBuf, old_path: String, new_path: String) -> Result<FileEntry, String> {
+    let old_full_path = resolve_within_root(&root, &old_path)?;
 
-    get_file_info(root, new_relative_path)
+    if !old_full_path.exists() {
+        return Err(format!("File not found: {}", old_path));
+    }
+
+    let new_file_path = if new_path.ends_with(".pn") {
+        new_path
+    } else {
+        format!("{}.pn", new_path)
+    };
+
+    let new_full_path = resolve_within_root(&root, &new_file_path)?;
+
+    if new_full_path.exists() {
+        return Err(format!("File already exists: {}", new_file_path));
+    }
+
+    if let Some(parent) = new_full_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    fs::rename(&old_full_path, &new_full_path).map_err(|e| format!("Failed to move: {}", e))?;
+
+    file_info(root, new_file_path)
+}
+
+/// Extensions left behind by merges, patch tools and editors
+const ARTIFACT_EXTENSIONS: &[&str] = &["orig", "bak", "rej", "swp", "tmp"];
+
+/// Suffixes of the files `git mergetool` writes next to a conflicted file
+const MERGE_ARTIFACT_MARKERS: &[&str] = &["_BACKUP_", "_BASE_", "_LOCAL_", "_REMOTE_"];
+
+/// List merge/backup artifact files in the workspace (relative paths)
+#[tauri::command]
+pub fn find_artifact_files(root: PathBuf) -> Result<Vec<String>, String> {
+    let mut artifacts = Vec::new();
+
+    if root.is_dir() {
+        collect_artifact_files(&root, &root, &mut artifacts)
+            .map_err(|e| format!("Failed to scan workspace: {}", e))?;
+    }
+
+    artifacts.sort();
+    Ok(artifacts)
+}
+
+/// Move the given artifact files to the trash
+/// Paths that aren't artifacts are rejected so notes can't be removed by mistake
+#[tauri::command]
+pub fn clean_artifact_files(root: PathBuf, paths: Vec<String>) -> Result<Vec<String>, String> {
+    if let Some(path) = paths.iter().find(|p| !is_artifact_file(Path::new(p))) {
+        return Err(format!("Not an artifact file: {}", path));
+    }
+
+    let mut cleaned = Vec::new();
+    for path in paths {
+        if !root.join(&path).is_file() {
+            continue;
+        }
+        move_to_trash(&root, &path)?;
+        cleaned.push(path);
+    }
+
+    Ok(cleaned)
+}
+
+fn collect_artifact_files(
+    root: &Path,
+    dir: &Path,
+    artifacts: &mut Vec<String>,
+) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        // Skip hidden files and directories
+        if path
+            .file_name()
+            .map(|n| n.to_string_lossy().starts_with('.'))
+            .unwrap_or(false)
+        {
+            continue;
+        }
+
+        if path.is_dir() {
+            collect_artifact_files(root, &path, artifacts)?;
+        } else if is_artifact_file(&path) {
+            if let Ok(relative) = path.strip_prefix(root) {
+                artifacts.push(relative.to_string_lossy().to_string());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn is_artifact_file(path: &Path) -> bool {
+    let Some(file_name) = path.file_name().map(|n| n.to_string_lossy()) else {
+        return false;
+    };
+
+    if file_name.ends_with('~') {
+        return true;
+    }
+
+    let is_artifact_extension = path
+        .extension()
+        .map(|e| ARTIFACT_EXTENSIONS.contains(&e.to_string_lossy().to_lowercase().as_str()))
+        .unwrap_or(false);
+
+    is_artifact_extension
+        || MERGE_ARTIFACT_MARKERS
+            .iter()
+            .any(|marker| file_name.contains(marker))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_within_root_accepts_paths_inside() {
+        let root = tempfile::tempdir().unwrap();
+        fs::write(root.path().join("note.pn"), "").unwrap();
+
+        assert_eq!(
+            resolve_within_root(root.path(), "note.pn").unwrap(),
+            root.path().join("note.pn")
+        );
+    }
+
+    #[test]
+    fn resolve_within_root_accepts_new_file_in_missing_directory() {
+        let root = tempfile::tempdir().unwrap();
+
+        assert_eq!(
+            resolve_within_root(root.path(), "new/dir/note.pn").unwrap(),
+            root.path().join("new/dir/note.pn")
+        );
+    }
+
+    #[test]
+    fn resolve_within_root_rejects_parent_traversal() {
+        let root = tempfile::tempdir().unwrap();
+        fs::create_dir(root.path().join("sub")).unwrap();
+
+        assert!(resolve_within_root(root.path(), "../outside.pn").is_err());
+        assert!(resolve_within_root(root.path(), "sub/../../outside.pn").is_err());
+        assert!(resolve_within_root(root.path(), "sub/../note.pn").is_err());
+    }
+
+    #[test]
+    fn resolve_within_root_rejects_absolute_paths() {
+        let root = tempfile::tempdir().unwrap();
+        let absolute = root.path().join("note.pn");
+
+        assert!(resolve_within_root(root.path(), &absolute.to_string_lossy()).is_err());
+        assert!(resolve_within_root(root.path(), "/etc/passwd").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resolve_within_root_rejects_symlinks_out_of_root() {
+        let root = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        fs::write(outside.path().join("secret.pn"), "").unwrap();
+        std::os::unix::fs::symlink(outside.path(), root.path().join("link")).unwrap();
+        std::os::unix::fs::symlink(
+            outside.path().join("missing.pn"),
+            root.path().join("dangling.pn"),
+        )
+        .unwrap();
+
+        assert!(resolve_within_root(root.path(), "link/secret.pn").is_err());
+        assert!(resolve_within_root(root.path(), "link/new.pn").is_err());
+        assert!(resolve_within_root(root.path(), "dangling.pn").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resolve_within_root_allows_symlinks_inside_root() {
+        let root = tempfile::tempdir().unwrap();
+        fs::create_dir(root.path().join("real")).unwrap();
+        std::os::unix::fs::symlink(root.path().join("real"), root.path().join("alias")).unwrap();
+
+        assert!(resolve_within_root(root.path(), "alias/note.pn").is_ok());
+    }
+
+    #[test]
+    fn move_to_trash_keeps_same_named_files_apart() {
+        let root = tempfile::tempdir().unwrap();
+        let mut trashed = Vec::new();
+        for content in ["first", "second", "third"] {
+            fs::write(root.path().join("note.pn"), content).unwrap();
+            trashed.push(move_to_trash(root.path(), "note.pn").unwrap());
+        }
+
+        let contents: Vec<String> = trashed
+            .iter()
+            .map(|path| fs::read_to_string(path).unwrap())
+            .collect();
+        assert_eq!(contents, ["first", "second", "third"]);
+
+        let entries = list_trash(root.path().to_path_buf()).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert!(entries.iter().all(|e| e.original_path == "note.pn"));
+    }
+
+    #[test]
+    fn trashed_file_with_timestamp_like_name_keeps_its_name() {
+        let root = tempfile::tempdir().unwrap();
+        fs::write(root.path().join("log.20240101120000.pn"), "").unwrap();
+        move_to_trash(root.path(), "log.20240101120000.pn").unwrap();
+
+        let entries = list_trash(root.path().to_path_buf()).unwrap();
+        assert_eq!(entries[0].original_path, "log.20240101120000.pn");
+    }
+
+    #[test]
+    fn restore_file_uses_recorded_original_path() {
+        let root = tempfile::tempdir().unwrap();
+        fs::create_dir(root.path().join("sub")).unwrap();
+        for _ in 0..2 {
+            fs::write(root.path().join("sub/note.pn"), "").unwrap();
+            move_to_trash(root.path(), "sub/note.pn").unwrap();
+        }
+
+        let suffixed = list_trash(root.path().to_path_buf())
+            .unwrap()
+            .into_iter()
+            .find(|e| e.path != Path::new("sub/note.pn").to_string_lossy())
+            .unwrap();
+        restore_file(root.path().to_path_buf(), suffixed.path).unwrap();
+
+        assert!(root.path().join("sub/note.pn").is_file());
+        assert_eq!(list_trash(root.path().to_path_buf()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn backlink_count_follows_invalidated_notes() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        fs::write(root.join("b.pn"), "target\n").unwrap();
+        fs::write(root.join("a.pn"), "[b]\n").unwrap();
+        fs::write(root.join("c.pn"), "[b]\n").unwrap();
+
+        let links = LinkCache::default();
+        assert_eq!(links.backlink_count(root, "b.pn").unwrap(), 2);
+
+        fs::write(root.join("c.pn"), "no links\n").unwrap();
+        links.invalidate(&root.join("c.pn"));
+        assert_eq!(links.backlink_count(root, "b.pn").unwrap(), 1);
+
+        fs::write(root.join("d.pn"), "[b] again\n").unwrap();
+        links.invalidate(&root.join("d.pn"));
+        assert_eq!(links.backlink_count(root, "b.pn").unwrap(), 2);
+
+        assert_eq!(links.backlink_counts(root).unwrap().get("b.pn"), Some(&2));
+    }
 }
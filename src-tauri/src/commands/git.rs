@@ -1,27 +1,69 @@
 // Git operations for patto-mobile
-// Using git2 crate with HTTPS + Personal Access Token authentication
+// Using git2 crate with HTTPS + Personal Access Token or SSH key authentication
 
+use crate::commands::credentials::resolve_credentials;
+use crate::commands::secrets::scan_files;
+use crate::commands::settings::{git_identity, GitIdentity, SettingsState};
 use git2::{
-    build::RepoBuilder, Cred, FetchOptions, PushOptions, RemoteCallbacks, Repository, Signature,
+    build::RepoBuilder, BranchType, Cred, FetchOptions, PushOptions, RemoteCallbacks, Repository,
+    Signature,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicU32, Ordering};
-use std::sync::Arc;
-use tauri::{AppHandle, Emitter};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, State};
 
 /// Result of git operations
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct GitResult {
     pub success: bool,
     pub message: String,
+    /// Files over the size threshold found while staging
+    #[serde(default)]
+    pub large_files: Vec<LargeFile>,
+    /// Files left conflicted by a merge
+    #[serde(default)]
+    pub conflicts: Vec<String>,
+    /// The local and remote branches have diverged and a merge is needed
+    #[serde(default)]
+    pub needs_merge: bool,
+    /// Commits only on the local branch, set with `needs_merge`
+    #[serde(default)]
+    pub ahead: usize,
+    /// Commits only on the remote branch, set with `needs_merge`
+    #[serde(default)]
+    pub behind: usize,
+}
+
+/// File exceeding the sync size threshold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LargeFile {
+    pub path: String,
+    pub size_bytes: u64,
 }
 
+/// Default size above which files are not staged by `git_sync` (10 MB)
+const DEFAULT_LARGE_FILE_THRESHOLD: u64 = 10 * 1024 * 1024;
+
 /// Git credentials stored in app state
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct GitCredentials {
     pub username: String,
     pub token: String,
+    /// Private key for SSH remotes, either a file path or inline PEM
+    #[serde(default)]
+    pub ssh_private_key: Option<String>,
+    #[serde(default)]
+    pub ssh_passphrase: Option<String>,
+}
+
+impl GitCredentials {
+    /// Nothing was passed, so stored credentials should be used
+    pub fn is_empty(&self) -> bool {
+        self.username.is_empty() && self.token.is_empty() && self.ssh_private_key.is_none()
+    }
 }
 
 /// Progress event for frontend
@@ -33,17 +75,42 @@ pub struct CloneProgress {
     pub percent: u32,
 }
 
+/// Cancellation flag for the clone in progress, set by `git_cancel_clone`
+#[derive(Default)]
+pub struct CloneState {
+    cancelled: Arc<AtomicBool>,
+}
+
 /// Create callbacks for authentication with optional progress reporting
+/// Fetch and push progress are emitted as `event`.
+/// Setting `cancel` aborts the transfer at the next progress update
 fn create_callbacks_with_progress<'a>(
     credentials: &'a GitCredentials,
     app_handle: Option<&'a AppHandle>,
+    event: &'static str,
     last_percent: Option<Arc<AtomicU32>>,
+    cancel: Option<Arc<AtomicBool>>,
 ) -> RemoteCallbacks<'a> {
     let mut callbacks = RemoteCallbacks::new();
     let username = credentials.username.clone();
     let token = credentials.token.clone();
+    let ssh_key = credentials.ssh_private_key.clone();
+    let ssh_passphrase = credentials.ssh_passphrase.clone();
+
+    callbacks.credentials(move |url, username_from_url, allowed_types| {
+        // SSH remotes authenticate with the private key, everything else with the token
+        if let Some(key) = ssh_key.as_deref() {
+            if is_ssh_url(url) && allowed_types.contains(git2::CredentialType::SSH_KEY) {
+                let user = username_from_url.unwrap_or("git");
+                let passphrase = ssh_passphrase.as_deref();
+                return if key.contains("PRIVATE KEY-----") {
+                    Cred::ssh_key_from_memory(user, None, key, passphrase)
+                } else {
+                    Cred::ssh_key(user, None, std::path::Path::new(key), passphrase)
+                };
+            }
+        }
 
-    callbacks.credentials(move |_url, _username_from_url, _allowed_types| {
         Cred::userpass_plaintext(&username, &token)
     });
 
@@ -52,49 +119,112 @@ fn create_callbacks_with_progress<'a>(
 
     // Add transfer progress reporting
     if let (Some(app), Some(last_pct)) = (app_handle, last_percent) {
-        let app = app.clone();
+        let fetch_app = app.clone();
+        let fetch_pct = last_pct.clone();
         callbacks.transfer_progress(move |stats| {
-            let received = stats.received_objects();
-            let total = stats.total_objects();
-            let percent = if total > 0 {
-                (received as u32 * 100) / total as u32
-            } else {
-                0
-            };
-
-            // Only emit every 5% to reduce event spam
-            let prev = last_pct.load(Ordering::Relaxed);
-            if percent >= prev + 5 || percent == 100 {
-                last_pct.store(percent, Ordering::Relaxed);
-                let _ = app.emit(
-                    "clone-progress",
-                    CloneProgress {
-                        stage: "Receiving objects".to_string(),
-                        received,
-                        total,
-                        percent,
-                    },
-                );
+            if cancel.as_ref().is_some_and(|c| c.load(Ordering::Relaxed)) {
+                return false;
             }
+
+            emit_transfer_progress(
+                &fetch_app,
+                event,
+                &fetch_pct,
+                "Receiving objects",
+                stats.received_objects(),
+                stats.total_objects(),
+            );
             true
         });
+
+        let push_app = app.clone();
+        callbacks.push_transfer_progress(move |current, total, _bytes| {
+            emit_transfer_progress(
+                &push_app,
+                event,
+                &last_pct,
+                "Writing objects",
+                current,
+                total,
+            );
+        });
     }
 
     callbacks
 }
 
+/// Emit a progress event, throttled to every 5%
+fn emit_transfer_progress(
+    app: &AppHandle,
+    event: &str,
+    last_pct: &AtomicU32,
+    stage: &str,
+    received: usize,
+    total: usize,
+) {
+    let percent = if total > 0 {
+        (received as u32 * 100) / total as u32
+    } else {
+        0
+    };
+
+    // Only emit every 5% to reduce event spam
+    let prev = last_pct.load(Ordering::Relaxed);
+    if percent >= prev + 5 || percent == 100 {
+        last_pct.store(percent, Ordering::Relaxed);
+        let _ = app.emit(
+            event,
+            CloneProgress {
+                stage: stage.to_string(),
+                received,
+                total,
+                percent,
+            },
+        );
+    }
+}
+
+/// `ssh://host/repo` or scp-like `git@host:repo`
+fn is_ssh_url(url: &str) -> bool {
+    if let Some((scheme, _)) = url.split_once("://") {
+        return scheme == "ssh" || scheme == "git+ssh";
+    }
+    url.contains('@') && url.contains(':')
+}
+
+/// Credentials for a named remote, falling back to the stored ones for its host
+fn remote_credentials(
+    app: &AppHandle,
+    repo: &Repository,
+    remote_name: &str,
+    credentials: GitCredentials,
+) -> GitCredentials {
+    match repo
+        .find_remote(remote_name)
+        .ok()
+        .and_then(|r| r.url().map(|u| u.to_string()))
+    {
+        Some(url) => resolve_credentials(app, &url, credentials),
+        None => credentials,
+    }
+}
+
 /// Create callbacks for authentication (without progress reporting)
 fn create_callbacks(credentials: &GitCredentials) -> RemoteCallbacks<'_> {
-    create_callbacks_with_progress(credentials, None, None)
+    create_callbacks_with_progress(credentials, None, "", None, None)
 }
 
 /// Clone a repository from a remote URL (with progress events)
+/// Can be aborted with `git_cancel_clone`, which removes the partial clone.
+/// With `depth`, only that many commits of history are fetched.
 #[tauri::command]
 pub async fn git_clone(
     app: AppHandle,
+    state: State<'_, CloneState>,
     url: String,
     dest: PathBuf,
     credentials: GitCredentials,
+    depth: Option<i32>,
 ) -> Result<GitResult, String> {
     // Emit starting event
     let _ = app.emit(
@@ -107,14 +237,27 @@ pub async fn git_clone(
         },
     );
 
+    let cancel = state.cancelled.clone();
+    cancel.store(false, Ordering::Relaxed);
+    let credentials = resolve_credentials(&app, &url, credentials);
+
     // Run clone in a blocking thread to not block the main thread
     let result = tokio::task::spawn_blocking(move || {
         let last_percent = Arc::new(AtomicU32::new(0));
-        let callbacks =
-            create_callbacks_with_progress(&credentials, Some(&app), Some(last_percent));
+        let dest_existed = dest.exists();
+        let callbacks = create_callbacks_with_progress(
+            &credentials,
+            Some(&app),
+            "clone-progress",
+            Some(last_percent),
+            Some(cancel.clone()),
+        );
 
         let mut fetch_options = FetchOptions::new();
         fetch_options.remote_callbacks(callbacks);
+        if let Some(depth) = depth.filter(|d| *d > 0) {
+            fetch_options.depth(depth);
+        }
 
         let mut builder = RepoBuilder::new();
         builder.fetch_options(fetch_options);
@@ -133,7 +276,7 @@ pub async fn git_clone(
                 );
 
                 // Fix file timestamps to match commit times (with progress)
-                let fixed = fix_file_timestamps(&repo, &dest, &app);
+                let fixed = fix_file_timestamps(&repo, &dest, &app, "clone-progress");
 
                 // Emit complete
                 let _ = app.emit(
@@ -152,6 +295,15 @@ pub async fn git_clone(
                         "Successfully cloned to {:?} (fixed {} file timestamps)",
                         dest, fixed
                     ),
+                    ..Default::default()
+                })
+            }
+            Err(_) if cancel.load(Ordering::Relaxed) => {
+                remove_partial_clone(&dest, dest_existed);
+                Ok(GitResult {
+                    success: false,
+                    message: "Clone cancelled".to_string(),
+                    ..Default::default()
                 })
             }
             Err(e) => Err(format!("Failed to clone repository: {}", e)),
@@ -163,16 +315,51 @@ pub async fn git_clone(
     result
 }
 
+/// Abort the clone in progress, if any
+#[tauri::command]
+pub fn git_cancel_clone(state: State<'_, CloneState>) -> Result<GitResult, String> {
+    state.cancelled.store(true, Ordering::Relaxed);
+
+    Ok(GitResult {
+        success: true,
+        message: "Cancelling clone".to_string(),
+        ..Default::default()
+    })
+}
+
+/// Delete what an aborted clone wrote, keeping `dest` itself if it existed beforehand
+fn remove_partial_clone(dest: &std::path::Path, dest_existed: bool) {
+    if !dest_existed {
+        let _ = std::fs::remove_dir_all(dest);
+        return;
+    }
+
+    if let Ok(entries) = std::fs::read_dir(dest) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let _ = if path.is_dir() {
+                std::fs::remove_dir_all(&path)
+            } else {
+                std::fs::remove_file(&path)
+            };
+        }
+    }
+}
+
 /// Set file modification times to their last commit time (optimized)
 /// Walks commits once to build file->timestamp map, then applies timestamps
-fn fix_file_timestamps(repo: &Repository, repo_path: &PathBuf, app: &AppHandle) -> usize {
+fn fix_file_timestamps(
+    repo: &Repository,
+    repo_path: &PathBuf,
+    app: &AppHandle,
+    event: &str,
+) -> usize {
     use std::collections::HashMap;
     use std::fs;
-    use std::time::{Duration, SystemTime};
 
     // Emit starting message
     let _ = app.emit(
-        "clone-progress",
+        event,
         CloneProgress {
             stage: "Building file history".to_string(),
             received: 0,
@@ -184,7 +371,14 @@ fn fix_file_timestamps(repo: &Repository, repo_path: &PathBuf, app: &AppHandle)
     // Build map of file -> last commit timestamp by walking commits once
     let mut file_times: HashMap<String, i64> = HashMap::new();
 
-    if let Ok(mut revwalk) = repo.revwalk() {
+    if repo.is_shallow() {
+        // Truncated history can't tell when files last changed, use the one commit we have
+        if let Ok(commit) = repo.head().and_then(|h| h.peel_to_commit()) {
+            if let Ok(tree) = commit.tree() {
+                insert_tree_times(&tree, commit.time().seconds(), &mut file_times);
+            }
+        }
+    } else if let Ok(mut revwalk) = repo.revwalk() {
         let _ = revwalk.push_head();
         let _ = revwalk.set_sorting(git2::Sort::TIME | git2::Sort::REVERSE); // oldest first
 
@@ -204,7 +398,7 @@ fn fix_file_timestamps(repo: &Repository, repo_path: &PathBuf, app: &AppHandle)
             if percent >= last_percent + 10 {
                 last_percent = percent;
                 let _ = app.emit(
-                    "clone-progress",
+                    event,
                     CloneProgress {
                         stage: "Analyzing commits".to_string(),
                         received: processed_commits,
@@ -219,13 +413,39 @@ fn fix_file_timestamps(repo: &Repository, repo_path: &PathBuf, app: &AppHandle)
 
                 if let Some(parent) = commit.parent(0).ok() {
                     if let (Ok(commit_tree), Ok(parent_tree)) = (commit.tree(), parent.tree()) {
-                        if let Ok(diff) =
+                        if let Ok(mut diff) =
                             repo.diff_tree_to_tree(Some(&parent_tree), Some(&commit_tree), None)
                         {
+                            // Pair deletes with adds so renames carry over to the new path
+                            let _ = diff.find_similar(None);
                             for delta in diff.deltas() {
-                                if let Some(path) = delta.new_file().path() {
-                                    file_times
-                                        .insert(path.to_string_lossy().to_string(), commit_time);
+                                let old_path = delta
+                                    .old_file()
+                                    .path()
+                                    .map(|p| p.to_string_lossy().to_string());
+                                let new_path = delta
+                                    .new_file()
+                                    .path()
+                                    .map(|p| p.to_string_lossy().to_string());
+                                match delta.status() {
+                                    git2::Delta::Deleted => {
+                                        if let Some(path) = old_path {
+                                            file_times.remove(&path);
+                                        }
+                                    }
+                                    git2::Delta::Renamed => {
+                                        if let Some(path) = old_path {
+                                            file_times.remove(&path);
+                                        }
+                                        if let Some(path) = new_path {
+                                            file_times.insert(path, commit_time);
+                                        }
+                                    }
+                                    _ => {
+                                        if let Some(path) = new_path {
+                                            file_times.insert(path, commit_time);
+                                        }
+                                    }
                                 }
                             }
                         }
@@ -233,17 +453,7 @@ fn fix_file_timestamps(repo: &Repository, repo_path: &PathBuf, app: &AppHandle)
                 } else {
                     // Initial commit - all files are new
                     if let Ok(tree) = commit.tree() {
-                        let _ = tree.walk(git2::TreeWalkMode::PreOrder, |dir, entry| {
-                            if entry.kind() == Some(git2::ObjectType::Blob) {
-                                let path = if dir.is_empty() {
-                                    entry.name().unwrap_or("").to_string()
-                                } else {
-                                    format!("{}{}", dir, entry.name().unwrap_or(""))
-                                };
-                                file_times.insert(path, commit_time);
-                            }
-                            git2::TreeWalkResult::Ok
-                        });
+                        insert_tree_times(&tree, commit_time, &mut file_times);
                     }
                 }
             }
@@ -266,7 +476,7 @@ fn fix_file_timestamps(repo: &Repository, repo_path: &PathBuf, app: &AppHandle)
         if percent >= last_percent + 10 || percent == 100 {
             last_percent = percent;
             let _ = app.emit(
-                "clone-progress",
+                event,
                 CloneProgress {
                     stage: "Setting timestamps".to_string(),
                     received: i + 1,
@@ -276,9 +486,18 @@ fn fix_file_timestamps(repo: &Repository, repo_path: &PathBuf, app: &AppHandle)
             );
         }
 
+        // Pre-1970 or far-future commit times would wrap into nonsense mtimes
+        let Some(mtime) = commit_time_to_mtime(*timestamp) else {
+            log::warn!(
+                "Skipping out-of-range commit time {} for {}",
+                timestamp,
+                path
+            );
+            continue;
+        };
+
         let file_path = repo_path.join(path);
         if file_path.exists() {
-            let mtime = SystemTime::UNIX_EPOCH + Duration::from_secs(*timestamp as u64);
             if let Ok(file) = fs::File::open(&file_path) {
                 let _ = file.set_modified(mtime);
                 count += 1;
@@ -289,33 +508,125 @@ fn fix_file_timestamps(repo: &Repository, repo_path: &PathBuf, app: &AppHandle)
     count
 }
 
-/// Pull changes from remote
+/// Record `time` for every file in `tree`
+fn insert_tree_times(
+    tree: &git2::Tree,
+    time: i64,
+    file_times: &mut std::collections::HashMap<String, i64>,
+) {
+    let _ = tree.walk(git2::TreeWalkMode::PreOrder, |dir, entry| {
+        if entry.kind() == Some(git2::ObjectType::Blob) {
+            let path = if dir.is_empty() {
+                entry.name().unwrap_or("").to_string()
+            } else {
+                format!("{}{}", dir, entry.name().unwrap_or(""))
+            };
+            file_times.insert(path, time);
+        }
+        git2::TreeWalkResult::Ok
+    });
+}
+
+/// Convert a commit time to a file mtime, rejecting values before the Unix epoch
+/// or more than a day in the future
+fn commit_time_to_mtime(timestamp: i64) -> Option<std::time::SystemTime> {
+    use std::time::{Duration, SystemTime};
+
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    let secs = u64::try_from(timestamp).ok()?;
+
+    if secs > now + 86400 {
+        return None;
+    }
+
+    Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+/// Re-derive file modification times from commit history at HEAD
+/// Repairs workspaces whose mtimes were corrupted, emits `normalize-progress` events
+#[tauri::command]
+pub async fn normalize_timestamps(app: AppHandle, repo_path: PathBuf) -> Result<GitResult, String> {
+    tokio::task::spawn_blocking(move || {
+        let repo =
+            Repository::open(&repo_path).map_err(|e| format!("Failed to open repo: {}", e))?;
+
+        let fixed = fix_file_timestamps(&repo, &repo_path, &app, "normalize-progress");
+
+        Ok(GitResult {
+            success: true,
+            message: format!("Normalized {} file timestamps", fixed),
+            ..Default::default()
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Name of the branch HEAD points at
+/// Errors with `DetachedHead:` or `NoCommitsYet:` instead of guessing a branch
+fn current_branch_name(repo: &Repository) -> Result<String, String> {
+    let head = match repo.head() {
+        Ok(head) => head,
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => {
+            return Err("NoCommitsYet: the repository has no commits yet".to_string());
+        }
+        Err(e) => return Err(format!("Failed to get HEAD: {}", e)),
+    };
+
+    if !head.is_branch() {
+        return Err("DetachedHead: HEAD is not on a branch".to_string());
+    }
+
+    head.shorthand()
+        .map(|s| s.to_string())
+        .ok_or_else(|| "Failed to get branch name: not valid UTF-8".to_string())
+}
+
+/// Pull changes from remote, emitting `pull-progress` events while fetching
+/// Diverged branches return `needs_merge` with ahead/behind counts unless `merge` is set.
+/// With `merge`, they are merged; on conflict the merge is left in progress and
+/// `conflicts` lists the files that need attention.
 #[tauri::command]
 pub async fn git_pull(
+    app: AppHandle,
+    settings: State<'_, SettingsState>,
     repo_path: PathBuf,
     credentials: GitCredentials,
+    remote: Option<String>,
+    merge: Option<bool>,
 ) -> Result<GitResult, String> {
+    let remote_name = remote.unwrap_or_else(|| "origin".to_string());
+    let merge = merge.unwrap_or(false);
+    let identity = git_identity(&app, &settings);
+
     tokio::task::spawn_blocking(move || {
         let repo =
             Repository::open(&repo_path).map_err(|e| format!("Failed to open repo: {}", e))?;
 
         // Get the current branch name
-        let head = repo
-            .head()
-            .map_err(|e| format!("Failed to get HEAD: {}", e))?;
-        let branch_name = head.shorthand().unwrap_or("main");
+        let branch_name = current_branch_name(&repo)?;
+        let credentials = remote_credentials(&app, &repo, &remote_name, credentials);
 
-        // Fetch from origin
+        // Fetch from remote
         let mut remote = repo
-            .find_remote("origin")
-            .map_err(|e| format!("Failed to find remote 'origin': {}", e))?;
-
-        let callbacks = create_callbacks(&credentials);
+            .find_remote(&remote_name)
+            .map_err(|e| format!("Failed to find remote '{}': {}", remote_name, e))?;
+
+        let callbacks = create_callbacks_with_progress(
+            &credentials,
+            Some(&app),
+            "pull-progress",
+            Some(Arc::new(AtomicU32::new(0))),
+            None,
+        );
         let mut fetch_options = FetchOptions::new();
         fetch_options.remote_callbacks(callbacks);
 
         remote
-            .fetch(&[branch_name], Some(&mut fetch_options), None)
+            .fetch(&[&branch_name], Some(&mut fetch_options), None)
             .map_err(|e| format!("Failed to fetch: {}", e))?;
 
         // Get fetch head
@@ -336,6 +647,7 @@ pub async fn git_pull(
             Ok(GitResult {
                 success: true,
                 message: "Already up to date".to_string(),
+                ..Default::default()
             })
         } else if analysis.0.is_fast_forward() {
             // Fast-forward merge
@@ -354,9 +666,78 @@ pub async fn git_pull(
             Ok(GitResult {
                 success: true,
                 message: "Fast-forward merge completed".to_string(),
+                ..Default::default()
+            })
+        } else if analysis.0.is_normal() && !merge {
+            // Diverged: let the user decide whether to merge
+            let local = repo
+                .head()
+                .and_then(|h| h.peel_to_commit())
+                .map_err(|e| format!("Failed to get HEAD commit: {}", e))?;
+            let (ahead, behind) = repo
+                .graph_ahead_behind(local.id(), fetch_commit.id())
+                .map_err(|e| format!("Failed to compare branches: {}", e))?;
+
+            Ok(GitResult {
+                success: false,
+                message: format!(
+                    "Branches have diverged ({} local, {} remote commits), merge required",
+                    ahead, behind
+                ),
+                needs_merge: true,
+                ahead,
+                behind,
+                ..Default::default()
             })
         } else {
-            Err("Merge required - manual intervention needed".to_string())
+            // Three-way merge into the working tree, leaving conflicts for the user to resolve
+            repo.merge(&[&fetch_commit], None, None)
+                .map_err(|e| format!("Failed to merge: {}", e))?;
+
+            let mut index = repo
+                .index()
+                .map_err(|e| format!("Failed to get index: {}", e))?;
+            if index.has_conflicts() {
+                let conflicts = conflicted_paths(&index)?;
+                return Ok(GitResult {
+                    success: false,
+                    message: format!("Merge conflicts in {} files", conflicts.len()),
+                    conflicts,
+                    ..Default::default()
+                });
+            }
+
+            let tree_id = index
+                .write_tree()
+                .map_err(|e| format!("Failed to write tree: {}", e))?;
+            let tree = repo
+                .find_tree(tree_id)
+                .map_err(|e| format!("Failed to find tree: {}", e))?;
+            let local = repo
+                .head()
+                .and_then(|h| h.peel_to_commit())
+                .map_err(|e| format!("Failed to get HEAD commit: {}", e))?;
+            let upstream = repo
+                .find_commit(fetch_commit.id())
+                .map_err(|e| format!("Failed to find fetched commit: {}", e))?;
+            let signature = commit_signature(identity.as_ref())?;
+            repo.commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                &format!("Merge {}/{} into {}", remote_name, branch_name, branch_name),
+                &tree,
+                &[&local, &upstream],
+            )
+            .map_err(|e| format!("Failed to commit merge: {}", e))?;
+            repo.cleanup_state()
+                .map_err(|e| format!("Failed to clean up merge state: {}", e))?;
+
+            Ok(GitResult {
+                success: true,
+                message: "Merge completed".to_string(),
+                ..Default::default()
+            })
         }
     })
     .await
@@ -364,26 +745,62 @@ pub async fn git_pull(
 }
 
 /// Commit all changes and push to remote
+/// An empty `message` is replaced by a summary of the staged changes.
+/// With `check_secrets`, the commit is refused if staged files look like they contain secrets.
+/// With `auto_rebase_on_reject`, a rejected push pulls the remote branch and retries once.
+/// With `paths`, only those files are staged and the rest stay uncommitted in the working tree.
+/// With `push` set to false, changes are only committed locally.
 #[tauri::command]
 pub async fn git_sync(
+    app: AppHandle,
+    state: State<'_, SyncState>,
+    settings: State<'_, SettingsState>,
     repo_path: PathBuf,
     message: String,
     credentials: GitCredentials,
+    remote: Option<String>,
+    force: Option<bool>,
+    large_file_threshold: Option<u64>,
+    check_secrets: Option<bool>,
+    auto_rebase_on_reject: Option<bool>,
+    paths: Option<Vec<String>>,
+    push: Option<bool>,
 ) -> Result<GitResult, String> {
-    tokio::task::spawn_blocking(move || {
+    let remote_name = remote.unwrap_or_else(|| "origin".to_string());
+    let push = push.unwrap_or(true);
+    let force = force.unwrap_or(false);
+    let check_secrets = check_secrets.unwrap_or(false);
+    let auto_rebase_on_reject = auto_rebase_on_reject.unwrap_or(false);
+    let threshold = large_file_threshold.unwrap_or(DEFAULT_LARGE_FILE_THRESHOLD);
+    let sync_path = repo_path.clone();
+    let sync_remote = remote_name.clone();
+    let identity = git_identity(&app, &settings);
+
+    let result = tokio::task::spawn_blocking(move || {
         let repo =
             Repository::open(&repo_path).map_err(|e| format!("Failed to open repo: {}", e))?;
 
-        // Stage all changes
+        // Committing on a detached HEAD would leave the commit unreachable
+        if repo.head_detached().unwrap_or(false) {
+            return Err(
+                "DetachedHead: HEAD is not on a branch, check out a branch before syncing"
+                    .to_string(),
+            );
+        }
+
+        // Large files are left unstaged unless forced
+        let large_files = find_large_files(&repo, threshold)?;
+        let skipped: Vec<String> = if force {
+            Vec::new()
+        } else {
+            large_files.iter().map(|f| f.path.clone()).collect()
+        };
+
+        // Stage the selected files, or all changes
         let mut index = repo
             .index()
             .map_err(|e| format!("Failed to get index: {}", e))?;
-        index
-            .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
-            .map_err(|e| format!("Failed to add files: {}", e))?;
-        index
-            .write()
-            .map_err(|e| format!("Failed to write index: {}", e))?;
+        stage_sync_changes(&repo_path, &mut index, paths.as_deref(), &skipped)?;
 
         // Check if there are changes to commit
         let tree_id = index
@@ -398,8 +815,7 @@ pub async fn git_sync(
         let parent_commit = head.as_ref().and_then(|h| h.peel_to_commit().ok());
 
         // Create signature
-        let signature = Signature::now("Patto Mobile", "patto@mobile.app")
-            .map_err(|e| format!("Failed to create signature: {}", e))?;
+        let signature = commit_signature(identity.as_ref())?;
 
         // Check if tree is different from parent
         let has_changes = match &parent_commit {
@@ -407,7 +823,37 @@ pub async fn git_sync(
             None => true,
         };
 
+        // Refuse to commit anything that looks like a pasted credential
+        if has_changes && check_secrets {
+            let parent_tree = parent_commit.as_ref().and_then(|c| c.tree().ok());
+            let paths = staged_paths(&repo, parent_tree.as_ref(), &tree)?;
+            let hits = scan_files(&repo_path, &paths);
+            if !hits.is_empty() {
+                let mut files: Vec<&str> = hits.iter().map(|h| h.file_path.as_str()).collect();
+                files.dedup();
+                return Err(format!(
+                    "SecretsFound: {} possible secrets in {}",
+                    hits.len(),
+                    files.join(", ")
+                ));
+            }
+        }
+
+        // Only persist the staging once the commit is going ahead, so a refused
+        // sync leaves the index as it was
+        index
+            .write()
+            .map_err(|e| format!("Failed to write index: {}", e))?;
+
         if has_changes {
+            // An empty message means "describe the change for me"
+            let commit_message = if message.trim().is_empty() {
+                let parent_tree = parent_commit.as_ref().and_then(|c| c.tree().ok());
+                auto_commit_message(&repo, parent_tree.as_ref(), &tree)?
+            } else {
+                message
+            };
+
             // Create commit
             let parents: Vec<&git2::Commit> =
                 parent_commit.as_ref().map(|c| vec![c]).unwrap_or_default();
@@ -415,62 +861,827 @@ pub async fn git_sync(
                 Some("HEAD"),
                 &signature,
                 &signature,
-                &message,
+                &commit_message,
                 &tree,
                 &parents,
             )
             .map_err(|e| format!("Failed to commit: {}", e))?;
         }
 
-        // Push to origin
-        let mut remote = repo
-            .find_remote("origin")
-            .map_err(|e| format!("Failed to find remote 'origin': {}", e))?;
+        let skipped_note = if skipped.is_empty() {
+            String::new()
+        } else {
+            format!(" ({} large files not staged)", skipped.len())
+        };
 
-        let callbacks = create_callbacks(&credentials);
-        let mut push_options = PushOptions::new();
-        push_options.remote_callbacks(callbacks);
+        // Offline commit, pushed by a later sync
+        if !push {
+            let mut message = if has_changes {
+                "Committed locally".to_string()
+            } else {
+                "No changes to commit".to_string()
+            };
+            if let Some((ahead, _)) = upstream_ahead_behind(&repo) {
+                message.push_str(&format!(" ({} commits ahead)", ahead));
+            }
+            message.push_str(&skipped_note);
 
-        let head = repo
-            .head()
-            .map_err(|e| format!("Failed to get HEAD: {}", e))?;
-        let branch_name = head.shorthand().unwrap_or("main");
-        let refspec = format!("refs/heads/{}:refs/heads/{}", branch_name, branch_name);
+            return Ok(GitResult {
+                success: true,
+                message,
+                large_files,
+                ..Default::default()
+            });
+        }
 
-        remote
-            .push(&[&refspec], Some(&mut push_options))
-            .map_err(|e| format!("Failed to push: {}", e))?;
+        // Push to remote, pulling and retrying once if the remote moved on
+        let branch_name = current_branch_name(&repo)?;
+        let credentials = remote_credentials(&app, &repo, &remote_name, credentials);
+        let mut auto_pulled = false;
+        match push_branch(&repo, &remote_name, &branch_name, &credentials, &app) {
+            Ok(()) => {}
+            Err(PushError::Rejected(_)) if auto_rebase_on_reject => {
+                merge_remote_branch(&repo, &remote_name, &branch_name, &credentials, &signature)?;
+                push_branch(&repo, &remote_name, &branch_name, &credentials, &app)
+                    .map_err(|e| format!("Failed to push: {}", e))?;
+                auto_pulled = true;
+            }
+            Err(e) => return Err(format!("Failed to push: {}", e)),
+        }
+
+        let mut message = if has_changes {
+            "Changes committed and pushed".to_string()
+        } else {
+            "No changes to commit, pushed to remote".to_string()
+        };
+        if auto_pulled {
+            message.push_str(" (pulled remote changes first)");
+        }
+        message.push_str(&skipped_note);
 
         Ok(GitResult {
             success: true,
-            message: if has_changes {
-                "Changes committed and pushed".to_string()
-            } else {
-                "No changes to commit, pushed to remote".to_string()
-            },
+            message,
+            large_files,
+            ..Default::default()
         })
     })
     .await
-    .map_err(|e| format!("Task join error: {}", e))?
+    .map_err(|e| format!("Task join error: {}", e))??;
+
+    // Remember the remote and what was pushed for `changes_since_last_sync`
+    state
+        .remotes
+        .lock()
+        .unwrap()
+        .insert(sync_path.clone(), sync_remote);
+    if let Some(oid) = Repository::open(&sync_path)
+        .ok()
+        .filter(|_| push)
+        .and_then(|repo| repo.head().ok().and_then(|h| h.target()))
+    {
+        state
+            .last_synced
+            .lock()
+            .unwrap()
+            .insert(sync_path, oid.to_string());
+    }
+
+    Ok(result)
+}
+
+/// Stage what `git_sync` commits: the selected `paths`, or all changes, minus `skipped`
+/// Shared with `git_preview_sync` so the preview matches the real sync.
+fn stage_sync_changes(
+    repo_path: &std::path::Path,
+    index: &mut git2::Index,
+    paths: Option<&[String]>,
+    skipped: &[String],
+) -> Result<(), String> {
+    let is_skipped =
+        |path: &std::path::Path| skipped.iter().any(|s| std::path::Path::new(s) == path);
+
+    match paths {
+        Some(paths) => {
+            for path in paths {
+                let relative = repo_relative_path(repo_path, path)?;
+                if is_skipped(&relative) {
+                    continue;
+                }
+                // A selected file that no longer exists is a deletion to commit
+                if repo_path.join(&relative).exists() {
+                    index
+                        .add_path(&relative)
+                        .map_err(|e| format!("Failed to add {}: {}", path, e))?;
+                } else {
+                    index
+                        .remove_path(&relative)
+                        .map_err(|e| format!("Failed to remove {}: {}", path, e))?;
+                }
+            }
+        }
+        None => {
+            let mut skip_large = |path: &std::path::Path, _spec: &[u8]| -> i32 {
+                if is_skipped(path) {
+                    1
+                } else {
+                    0
+                }
+            };
+            index
+                .add_all(
+                    ["*"].iter(),
+                    git2::IndexAddOption::DEFAULT,
+                    Some(&mut skip_large as &mut git2::IndexMatchedPath),
+                )
+                .map_err(|e| format!("Failed to add files: {}", e))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Path relative to the repository root, accepting either relative or absolute input
+/// Errors if the path points outside the repository
+fn repo_relative_path(repo_path: &std::path::Path, path: &str) -> Result<PathBuf, String> {
+    let path = std::path::Path::new(path);
+    let relative = if path.is_absolute() {
+        path.strip_prefix(repo_path)
+            .map_err(|_| format!("Path is outside the repository: {}", path.display()))?
+    } else {
+        path
+    };
+
+    if relative
+        .components()
+        .any(|c| !matches!(c, std::path::Component::Normal(_)))
+    {
+        return Err(format!(
+            "Path is outside the repository: {}",
+            path.display()
+        ));
+    }
+
+    Ok(relative.to_path_buf())
+}
+
+/// Commit last pushed by `git_sync` and the remote it synced with, per repository
+#[derive(Default)]
+pub struct SyncState {
+    last_synced: Mutex<HashMap<PathBuf, String>>,
+    remotes: Mutex<HashMap<PathBuf, String>>,
 }
 
-/// Configure remote URL for existing repository
+/// Files changed since the last sync
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSummary {
+    /// Commit the working tree was compared against
+    pub base_commit: Option<String>,
+    pub added: usize,
+    pub modified: usize,
+    pub deleted: usize,
+    pub files: Vec<SyncFile>,
+}
+
+/// Diff the working tree against the last pushed commit
+/// Falls back to the remote-tracking branch of the last synced remote (default `origin`)
+/// if nothing was pushed in this session, and to the empty tree if the branch was never pushed.
 #[tauri::command]
-pub fn configure_remote(repo_path: PathBuf, remote_url: String) -> Result<GitResult, String> {
+pub fn changes_since_last_sync(
+    state: State<'_, SyncState>,
+    repo_path: PathBuf,
+) -> Result<ChangeSummary, String> {
     let repo = Repository::open(&repo_path).map_err(|e| format!("Failed to open repo: {}", e))?;
 
-    // Check if origin exists
-    if repo.find_remote("origin").is_ok() {
-        repo.remote_set_url("origin", &remote_url)
+    let recorded = state.last_synced.lock().unwrap().get(&repo_path).cloned();
+    let remote_name = state
+        .remotes
+        .lock()
+        .unwrap()
+        .get(&repo_path)
+        .cloned()
+        .unwrap_or_else(|| "origin".to_string());
+    let base_oid = match recorded {
+        Some(oid) => Some(git2::Oid::from_str(&oid).map_err(|e| format!("Invalid oid: {}", e))?),
+        None => current_branch_name(&repo).ok().and_then(|branch| {
+            repo.refname_to_id(&format!("refs/remotes/{}/{}", remote_name, branch))
+                .ok()
+        }),
+    };
+
+    let base_tree = match base_oid {
+        Some(oid) => Some(
+            repo.find_commit(oid)
+                .and_then(|c| c.tree())
+                .map_err(|e| format!("Failed to find commit {}: {}", oid, e))?,
+        ),
+        None => None,
+    };
+
+    let mut options = git2::DiffOptions::new();
+    options.include_untracked(true).recurse_untracked_dirs(true);
+    let mut diff = repo
+        .diff_tree_to_workdir_with_index(base_tree.as_ref(), Some(&mut options))
+        .map_err(|e| format!("Failed to diff: {}", e))?;
+    diff.find_similar(None)
+        .map_err(|e| format!("Failed to detect renames: {}", e))?;
+
+    let mut summary = ChangeSummary {
+        base_commit: base_oid.map(|oid| oid.to_string()),
+        added: 0,
+        modified: 0,
+        deleted: 0,
+        files: Vec::new(),
+    };
+
+    for delta in diff.deltas() {
+        let status = match delta.status() {
+            git2::Delta::Added | git2::Delta::Untracked | git2::Delta::Copied => {
+                summary.added += 1;
+                "new"
+            }
+            git2::Delta::Deleted => {
+                summary.deleted += 1;
+                "deleted"
+            }
+            git2::Delta::Renamed => {
+                summary.modified += 1;
+                "renamed"
+            }
+            _ => {
+                summary.modified += 1;
+                "modified"
+            }
+        };
+
+        if let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path()) {
+            summary.files.push(SyncFile {
+                path: path.to_string_lossy().to_string(),
+                status: status.to_string(),
+            });
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Why a push failed
+enum PushError {
+    /// The remote refused a non-fast-forward update
+    Rejected(String),
+    Other(String),
+}
+
+impl std::fmt::Display for PushError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PushError::Rejected(reason) => write!(f, "rejected by remote ({})", reason),
+            PushError::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// Push the branch to the same-named branch on the remote, emitting `push-progress` events
+fn push_branch(
+    repo: &Repository,
+    remote_name: &str,
+    branch_name: &str,
+    credentials: &GitCredentials,
+    app: &AppHandle,
+) -> Result<(), PushError> {
+    let mut remote = repo
+        .find_remote(remote_name)
+        .map_err(|e| PushError::Other(format!("Failed to find remote '{}': {}", remote_name, e)))?;
+
+    // Server-side rejections arrive through this callback, not as an error
+    let rejection = std::cell::RefCell::new(None);
+    let mut callbacks = create_callbacks_with_progress(
+        credentials,
+        Some(app),
+        "push-progress",
+        Some(Arc::new(AtomicU32::new(0))),
+        None,
+    );
+    callbacks.push_update_reference(|_refname, status| {
+        if let Some(status) = status {
+            *rejection.borrow_mut() = Some(status.to_string());
+        }
+        Ok(())
+    });
+    let mut push_options = PushOptions::new();
+    push_options.remote_callbacks(callbacks);
+
+    let refspec = format!("refs/heads/{}:refs/heads/{}", branch_name, branch_name);
+    if let Err(e) = remote.push(&[&refspec], Some(&mut push_options)) {
+        return Err(if e.code() == git2::ErrorCode::NotFastForward {
+            PushError::Rejected(e.message().to_string())
+        } else {
+            PushError::Other(e.to_string())
+        });
+    }
+    drop(push_options);
+
+    match rejection.into_inner() {
+        Some(reason) => Err(PushError::Rejected(reason)),
+        None => Ok(()),
+    }
+}
+
+/// Fetch the remote branch and fast-forward or merge it into the current branch
+/// Errors with `Conflict:` if the merge can't be done automatically, and with
+/// `UncommittedChanges:` if it would overwrite edits that weren't committed.
+fn merge_remote_branch(
+    repo: &Repository,
+    remote_name: &str,
+    branch_name: &str,
+    credentials: &GitCredentials,
+    signature: &Signature,
+) -> Result<(), String> {
+    let mut remote = repo
+        .find_remote(remote_name)
+        .map_err(|e| format!("Failed to find remote '{}': {}", remote_name, e))?;
+    let mut fetch_options = FetchOptions::new();
+    fetch_options.remote_callbacks(create_callbacks(credentials));
+    remote
+        .fetch(&[branch_name], Some(&mut fetch_options), None)
+        .map_err(|e| format!("Failed to fetch: {}", e))?;
+
+    let fetch_head = repo
+        .find_reference("FETCH_HEAD")
+        .map_err(|e| format!("Failed to find FETCH_HEAD: {}", e))?;
+    let fetch_commit = repo
+        .reference_to_annotated_commit(&fetch_head)
+        .map_err(|e| format!("Failed to get fetch commit: {}", e))?;
+
+    let analysis = repo
+        .merge_analysis(&[&fetch_commit])
+        .map_err(|e| format!("Failed merge analysis: {}", e))?;
+    if analysis.0.is_up_to_date() {
+        return Ok(());
+    }
+
+    let upstream = repo
+        .find_commit(fetch_commit.id())
+        .map_err(|e| format!("Failed to find fetched commit: {}", e))?;
+
+    let refname = format!("refs/heads/{}", branch_name);
+    if analysis.0.is_fast_forward() {
+        // Update the working tree before moving the branch so a refused checkout changes nothing
+        safe_checkout(repo, upstream.as_object())?;
+        let mut reference = repo
+            .find_reference(&refname)
+            .map_err(|e| format!("Failed to find reference: {}", e))?;
+        reference
+            .set_target(fetch_commit.id(), "Fast-forward")
+            .map_err(|e| format!("Failed to set target: {}", e))?;
+        return Ok(());
+    }
+
+    // Diverged: merge in memory so a conflict leaves the working tree untouched
+    let local = repo
+        .head()
+        .and_then(|h| h.peel_to_commit())
+        .map_err(|e| format!("Failed to get HEAD commit: {}", e))?;
+    let mut index = repo
+        .merge_commits(&local, &upstream, None)
+        .map_err(|e| format!("Failed to merge: {}", e))?;
+
+    if index.has_conflicts() {
+        let paths = conflicted_paths(&index)?;
+        return Err(format!(
+            "Conflict: remote changes conflict with local edits in {}",
+            paths.join(", ")
+        ));
+    }
+
+    let tree_id = index
+        .write_tree_to(repo)
+        .map_err(|e| format!("Failed to write tree: {}", e))?;
+    let tree = repo
+        .find_tree(tree_id)
+        .map_err(|e| format!("Failed to find tree: {}", e))?;
+    safe_checkout(repo, tree.as_object())?;
+    repo.commit(
+        Some("HEAD"),
+        signature,
+        signature,
+        &format!("Merge {}/{} into {}", remote_name, branch_name, branch_name),
+        &tree,
+        &[&local, &upstream],
+    )
+    .map_err(|e| format!("Failed to commit merge: {}", e))?;
+
+    Ok(())
+}
+
+/// Check out `target` relative to HEAD, refusing to overwrite uncommitted edits
+/// Sync may leave unstaged files (`paths`, skipped large files) in the working tree.
+fn safe_checkout(repo: &Repository, target: &git2::Object) -> Result<(), String> {
+    repo.checkout_tree(target, Some(git2::build::CheckoutBuilder::default().safe()))
+        .map_err(|e| {
+            if e.code() == git2::ErrorCode::Conflict {
+                "UncommittedChanges: remote changes would overwrite local edits that weren't committed"
+                    .to_string()
+            } else {
+                format!("Failed to checkout: {}", e)
+            }
+        })
+}
+
+/// Paths with conflict entries in a merge index
+fn conflicted_paths(index: &git2::Index) -> Result<Vec<String>, String> {
+    Ok(index
+        .conflicts()
+        .map_err(|e| format!("Failed to read conflicts: {}", e))?
+        .filter_map(|c| c.ok())
+        .filter_map(|c| c.our.or(c.their).or(c.ancestor))
+        .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+        .collect())
+}
+
+/// Files added or modified between the parent tree and the staged tree
+fn staged_paths(
+    repo: &Repository,
+    parent_tree: Option<&git2::Tree>,
+    tree: &git2::Tree,
+) -> Result<Vec<String>, String> {
+    let diff = repo
+        .diff_tree_to_tree(parent_tree, Some(tree), None)
+        .map_err(|e| format!("Failed to diff: {}", e))?;
+
+    Ok(diff
+        .deltas()
+        .filter(|delta| delta.status() != git2::Delta::Deleted)
+        .filter_map(|delta| delta.new_file().path())
+        .map(|p| p.to_string_lossy().to_string())
+        .collect())
+}
+
+/// Summarize the staged changes, e.g. "Update 3 notes, add 1"
+/// A single changed file is named instead of counted
+fn auto_commit_message(
+    repo: &Repository,
+    parent_tree: Option<&git2::Tree>,
+    tree: &git2::Tree,
+) -> Result<String, String> {
+    let mut diff = repo
+        .diff_tree_to_tree(parent_tree, Some(tree), None)
+        .map_err(|e| format!("Failed to diff: {}", e))?;
+    diff.find_similar(None)
+        .map_err(|e| format!("Failed to detect renames: {}", e))?;
+
+    let mut updated = 0;
+    let mut added = 0;
+    let mut deleted = 0;
+    let mut renamed = 0;
+    let mut last_path = None;
+
+    for delta in diff.deltas() {
+        match delta.status() {
+            git2::Delta::Added | git2::Delta::Copied => added += 1,
+            git2::Delta::Deleted => deleted += 1,
+            git2::Delta::Renamed => renamed += 1,
+            _ => updated += 1,
+        }
+        last_path = delta
+            .new_file()
+            .path()
+            .or_else(|| delta.old_file().path())
+            .map(|p| p.to_string_lossy().to_string());
+    }
+
+    if diff.deltas().len() == 1 {
+        if let Some(path) = last_path {
+            let verb = match (added, deleted, renamed) {
+                (1, _, _) => "Add",
+                (_, 1, _) => "Delete",
+                (_, _, 1) => "Rename",
+                _ => "Update",
+            };
+            return Ok(format!("{} {}", verb, path));
+        }
+    }
+
+    let mut parts = Vec::new();
+    for (verb, count) in [
+        ("update", updated),
+        ("add", added),
+        ("delete", deleted),
+        ("rename", renamed),
+    ] {
+        if count > 0 {
+            parts.push(format!("{} {}", verb, count));
+        }
+    }
+
+    let Some(first) = parts.first_mut() else {
+        return Ok("Sync notes".to_string());
+    };
+    // "update 3" -> "Update 3 notes"
+    first.push_str(" notes");
+    first[..1].make_ascii_uppercase();
+
+    Ok(parts.join(", "))
+}
+
+/// Switch to `branch`, creating a tracking branch from `origin/<branch>` if only the remote has it
+/// Errors with `UncommittedChanges:` if local edits would be overwritten.
+/// Returns the new current branch name as the message.
+#[tauri::command]
+pub fn git_checkout(repo_path: PathBuf, branch: String) -> Result<GitResult, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| format!("Failed to open repo: {}", e))?;
+
+    let local = match repo.find_branch(&branch, BranchType::Local) {
+        Ok(local) => local,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => {
+            let remote_branch = format!("origin/{}", branch);
+            let commit = repo
+                .find_branch(&remote_branch, BranchType::Remote)
+                .map_err(|_| format!("Branch not found: {}", branch))?
+                .get()
+                .peel_to_commit()
+                .map_err(|e| format!("Failed to resolve {}: {}", remote_branch, e))?;
+            let mut local = repo
+                .branch(&branch, &commit, false)
+                .map_err(|e| format!("Failed to create branch: {}", e))?;
+            local
+                .set_upstream(Some(&remote_branch))
+                .map_err(|e| format!("Failed to set upstream: {}", e))?;
+            local
+        }
+        Err(e) => return Err(format!("Failed to find branch: {}", e)),
+    };
+
+    let refname = local
+        .get()
+        .name()
+        .ok_or_else(|| "Failed to get branch name: not valid UTF-8".to_string())?
+        .to_string();
+    let target = local
+        .get()
+        .peel(git2::ObjectType::Commit)
+        .map_err(|e| format!("Failed to resolve {}: {}", branch, e))?;
+
+    // Update the working tree before moving HEAD so a refused checkout changes nothing
+    repo.checkout_tree(
+        &target,
+        Some(git2::build::CheckoutBuilder::default().safe()),
+    )
+    .map_err(|e| {
+        if e.code() == git2::ErrorCode::Conflict {
+            "UncommittedChanges: commit or discard local changes before switching branches"
+                .to_string()
+        } else {
+            format!("Failed to checkout: {}", e)
+        }
+    })?;
+    repo.set_head(&refname)
+        .map_err(|e| format!("Failed to set HEAD: {}", e))?;
+
+    Ok(GitResult {
+        success: true,
+        message: current_branch_name(&repo)?,
+        ..Default::default()
+    })
+}
+
+/// Discard local changes and move the current branch to `target` (default `HEAD`)
+/// `target` must resolve to a commit in this repository. Untracked files are kept.
+/// Returns the number of reverted files in the message.
+#[tauri::command]
+pub fn git_reset_hard(repo_path: PathBuf, target: Option<String>) -> Result<GitResult, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| format!("Failed to open repo: {}", e))?;
+    let target = target.unwrap_or_else(|| "HEAD".to_string());
+
+    let commit = repo
+        .revparse_single(&target)
+        .and_then(|obj| obj.peel_to_commit())
+        .map_err(|_| format!("Target not found in repository: {}", target))?;
+    let tree = commit
+        .tree()
+        .map_err(|e| format!("Failed to get tree: {}", e))?;
+
+    // Tracked files that differ from the target are the ones the reset overwrites
+    let reverted = repo
+        .diff_tree_to_workdir_with_index(Some(&tree), None)
+        .map_err(|e| format!("Failed to diff: {}", e))?
+        .deltas()
+        .len();
+
+    repo.reset(commit.as_object(), git2::ResetType::Hard, None)
+        .map_err(|e| format!("Failed to reset: {}", e))?;
+
+    Ok(GitResult {
+        success: true,
+        message: format!(
+            "Reset to {} ({} files reverted)",
+            &commit.id().to_string()[..7],
+            reverted
+        ),
+        ..Default::default()
+    })
+}
+
+/// Back out of an in-progress merge, like `git merge --abort`
+/// Resets the index and working tree to HEAD and clears the merge state.
+#[tauri::command]
+pub fn git_abort_merge(repo_path: PathBuf) -> Result<GitResult, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| format!("Failed to open repo: {}", e))?;
+
+    if repo.state() != git2::RepositoryState::Merge {
+        return Err("NoMergeInProgress: there is no merge to abort".to_string());
+    }
+
+    let head = repo
+        .head()
+        .and_then(|h| h.peel_to_commit())
+        .map_err(|e| format!("Failed to get HEAD commit: {}", e))?;
+    repo.reset(head.as_object(), git2::ResetType::Hard, None)
+        .map_err(|e| format!("Failed to reset: {}", e))?;
+    repo.cleanup_state()
+        .map_err(|e| format!("Failed to clean up merge state: {}", e))?;
+
+    Ok(GitResult {
+        success: true,
+        message: "Merge aborted".to_string(),
+        ..Default::default()
+    })
+}
+
+/// Branches available for switching
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BranchList {
+    /// None when HEAD is detached or unborn
+    pub current: Option<String>,
+    pub local: Vec<String>,
+    /// Remote branch names, `origin/` prefix stripped
+    pub remote: Vec<String>,
+}
+
+/// List local and remote branches
+#[tauri::command]
+pub fn git_branches(repo_path: PathBuf) -> Result<BranchList, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| format!("Failed to open repo: {}", e))?;
+
+    let branch_names = |kind: BranchType| -> Result<Vec<String>, String> {
+        let branches = repo
+            .branches(Some(kind))
+            .map_err(|e| format!("Failed to list branches: {}", e))?;
+        Ok(branches
+            .flatten()
+            .filter_map(|(branch, _)| branch.name().ok().flatten().map(|n| n.to_string()))
+            .collect())
+    };
+
+    let local = branch_names(BranchType::Local)?;
+    let remote = branch_names(BranchType::Remote)?
+        .into_iter()
+        .map(|name| match name.strip_prefix("origin/") {
+            Some(short) => short.to_string(),
+            None => name,
+        })
+        .filter(|name| name != "HEAD" && !name.ends_with("/HEAD"))
+        .collect();
+
+    Ok(BranchList {
+        current: current_branch_name(&repo).ok(),
+        local,
+        remote,
+    })
+}
+
+/// Configure remote URL for existing repository (defaults to `origin`)
+#[tauri::command]
+pub fn configure_remote(
+    repo_path: PathBuf,
+    remote_url: String,
+    remote_name: Option<String>,
+) -> Result<GitResult, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| format!("Failed to open repo: {}", e))?;
+    let remote_name = remote_name.unwrap_or_else(|| "origin".to_string());
+
+    // Check if remote exists
+    if repo.find_remote(&remote_name).is_ok() {
+        repo.remote_set_url(&remote_name, &remote_url)
             .map_err(|e| format!("Failed to set remote URL: {}", e))?;
     } else {
-        repo.remote("origin", &remote_url)
+        repo.remote(&remote_name, &remote_url)
             .map_err(|e| format!("Failed to add remote: {}", e))?;
     }
 
     Ok(GitResult {
         success: true,
-        message: format!("Remote 'origin' set to {}", remote_url),
+        message: format!("Remote '{}' set to {}", remote_name, remote_url),
+        ..Default::default()
+    })
+}
+
+/// Remote name and URL
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RemoteInfo {
+    pub name: String,
+    pub url: Option<String>,
+}
+
+/// List configured remotes
+#[tauri::command]
+pub fn git_remotes(repo_path: PathBuf) -> Result<Vec<RemoteInfo>, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| format!("Failed to open repo: {}", e))?;
+
+    let names = repo
+        .remotes()
+        .map_err(|e| format!("Failed to list remotes: {}", e))?;
+
+    Ok(names
+        .iter()
+        .flatten()
+        .map(|name| RemoteInfo {
+            name: name.to_string(),
+            url: repo
+                .find_remote(name)
+                .ok()
+                .and_then(|r| r.url().map(|u| u.to_string())),
+        })
+        .collect())
+}
+
+/// Number of commits returned by `git_log` when no limit is given
+const DEFAULT_LOG_LIMIT: usize = 100;
+
+/// Commit shown in the history view
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommitInfo {
+    pub id: String,
+    pub short_id: String,
+    pub summary: String,
+    pub author_name: String,
+    pub author_email: String,
+    pub timestamp: i64,
+    pub parent_count: usize,
+}
+
+/// List commits reachable from HEAD, newest first (at most `limit`, default 100)
+#[tauri::command]
+pub fn git_log(repo_path: PathBuf, limit: Option<usize>) -> Result<Vec<CommitInfo>, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| format!("Failed to open repo: {}", e))?;
+
+    // A fresh repository has no history yet
+    if matches!(repo.head(), Err(e) if e.code() == git2::ErrorCode::UnbornBranch) {
+        return Ok(Vec::new());
+    }
+
+    let mut revwalk = repo
+        .revwalk()
+        .map_err(|e| format!("Failed to walk history: {}", e))?;
+    revwalk
+        .push_head()
+        .map_err(|e| format!("Failed to walk history: {}", e))?;
+    revwalk
+        .set_sorting(git2::Sort::TIME)
+        .map_err(|e| format!("Failed to walk history: {}", e))?;
+
+    revwalk
+        .take(limit.unwrap_or(DEFAULT_LOG_LIMIT))
+        .map(|oid| {
+            let oid = oid.map_err(|e| format!("Failed to walk history: {}", e))?;
+            let commit = repo
+                .find_commit(oid)
+                .map_err(|e| format!("Failed to find commit {}: {}", oid, e))?;
+            let author = commit.author();
+            let id = oid.to_string();
+
+            Ok(CommitInfo {
+                short_id: id.chars().take(7).collect(),
+                id,
+                summary: commit.summary().unwrap_or("").to_string(),
+                author_name: author.name().unwrap_or("").to_string(),
+                author_email: author.email().unwrap_or("").to_string(),
+                timestamp: commit.time().seconds(),
+                parent_count: commit.parent_count(),
+            })
+        })
+        .collect()
+}
+
+/// Whether a workspace is managed by git or is a plain synced folder
+#[derive(Debug, Serialize, Deserialize)]
+pub enum WorkspaceMode {
+    Git,
+    PlainFolder,
+}
+
+/// Report whether the workspace is a git repository, so the UI can hide git actions
+/// Only the git commands require a repository; file, note and task commands work in both modes
+#[tauri::command]
+pub fn workspace_mode(root: PathBuf) -> Result<WorkspaceMode, String> {
+    if !root.is_dir() {
+        return Err(format!("Workspace not found: {:?}", root));
+    }
+
+    Ok(match Repository::open(&root) {
+        Ok(_) => WorkspaceMode::Git,
+        Err(_) => WorkspaceMode::PlainFolder,
     })
 }
 
@@ -481,27 +1692,99 @@ pub fn git_init(repo_path: PathBuf) -> Result<GitResult, String> {
         Ok(_repo) => Ok(GitResult {
             success: true,
             message: format!("Initialized empty repository in {:?}", repo_path),
+            ..Default::default()
         }),
         Err(e) => Err(format!("Failed to initialize repository: {}", e)),
     }
 }
 
+/// Create the first commit of a freshly initialized repository on `main`
+#[tauri::command]
+pub fn git_initial_commit(
+    app: AppHandle,
+    settings: State<'_, SettingsState>,
+    repo_path: PathBuf,
+    message: String,
+) -> Result<GitResult, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| format!("Failed to open repo: {}", e))?;
+
+    if repo.head().is_ok() {
+        return Err("Repository already has commits".to_string());
+    }
+
+    // Point the unborn HEAD at main so the commit creates that branch
+    repo.set_head("refs/heads/main")
+        .map_err(|e| format!("Failed to set HEAD: {}", e))?;
+
+    // Stage all files
+    let mut index = repo
+        .index()
+        .map_err(|e| format!("Failed to get index: {}", e))?;
+    index
+        .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+        .map_err(|e| format!("Failed to add files: {}", e))?;
+    index
+        .write()
+        .map_err(|e| format!("Failed to write index: {}", e))?;
+
+    let tree_id = index
+        .write_tree()
+        .map_err(|e| format!("Failed to write tree: {}", e))?;
+    let tree = repo
+        .find_tree(tree_id)
+        .map_err(|e| format!("Failed to find tree: {}", e))?;
+
+    let signature = commit_signature(git_identity(&app, &settings).as_ref())?;
+
+    let commit_id = repo
+        .commit(Some("HEAD"), &signature, &signature, &message, &tree, &[])
+        .map_err(|e| format!("Failed to commit: {}", e))?;
+
+    Ok(GitResult {
+        success: true,
+        message: format!("Created initial commit {} on main", commit_id),
+        ..Default::default()
+    })
+}
+
+/// Signature used for commits made from the app
+/// Uses the configured identity, or a generic app identity if none is set
+fn commit_signature(identity: Option<&GitIdentity>) -> Result<Signature<'static>, String> {
+    match identity {
+        Some(identity) => Signature::now(&identity.name, &identity.email),
+        None => Signature::now("Patto Mobile", "patto@mobile.app"),
+    }
+    .map_err(|e| format!("Failed to create signature: {}", e))
+}
+
 /// Get repository status summary
 #[tauri::command]
 pub fn git_status(repo_path: PathBuf) -> Result<GitStatus, String> {
     let repo = Repository::open(&repo_path).map_err(|e| format!("Failed to open repo: {}", e))?;
 
+    // Same flags as the defaults, plus rename detection for staged files
+    let mut options = git2::StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(true)
+        .renames_head_to_index(true);
     let statuses = repo
-        .statuses(None)
+        .statuses(Some(&mut options))
         .map_err(|e| format!("Failed to get status: {}", e))?;
 
     let mut modified = 0;
     let mut added = 0;
     let mut deleted = 0;
     let mut untracked = 0;
+    let mut files = Vec::new();
 
     for entry in statuses.iter() {
         let status = entry.status();
+        if !status.is_ignored() {
+            files.push(file_status(&entry));
+        }
+
         if status.is_wt_modified() || status.is_index_modified() {
             modified += 1;
         }
@@ -517,10 +1800,19 @@ pub fn git_status(repo_path: PathBuf) -> Result<GitStatus, String> {
     }
 
     // Get current branch
-    let head = repo.head().ok();
-    let branch = head
-        .as_ref()
-        .and_then(|h| h.shorthand().map(|s| s.to_string()));
+    // On a fresh repo HEAD points at an unborn branch, read its name from the symbolic ref
+    let head = repo.head();
+    let has_commits = !matches!(&head, Err(e) if e.code() == git2::ErrorCode::UnbornBranch);
+    let branch = match head {
+        Ok(head) => head.shorthand().map(|s| s.to_string()),
+        Err(_) => repo
+            .find_reference("HEAD")
+            .ok()
+            .and_then(|r| r.symbolic_target().map(|t| t.to_string()))
+            .map(|t| t.trim_start_matches("refs/heads/").to_string()),
+    };
+
+    let upstream = upstream_ahead_behind(&repo);
 
     Ok(GitStatus {
         branch,
@@ -529,9 +1821,99 @@ pub fn git_status(repo_path: PathBuf) -> Result<GitStatus, String> {
         deleted,
         untracked,
         is_clean: statuses.is_empty(),
+        has_commits,
+        files,
+        has_upstream: upstream.is_some(),
+        ahead: upstream.map_or(0, |(ahead, _)| ahead),
+        behind: upstream.map_or(0, |(_, behind)| behind),
     })
 }
 
+/// Commits the current branch is ahead of and behind its upstream
+/// None when HEAD is not on a branch or the branch has no upstream
+fn upstream_ahead_behind(repo: &Repository) -> Option<(usize, usize)> {
+    let branch_name = current_branch_name(repo).ok()?;
+    let branch = repo.find_branch(&branch_name, BranchType::Local).ok()?;
+    let local_oid = branch.get().target()?;
+    let upstream_oid = branch.upstream().ok()?.get().target()?;
+
+    repo.graph_ahead_behind(local_oid, upstream_oid).ok()
+}
+
+/// Unified diff of one file in the working tree against HEAD
+/// An untracked file is shown as entirely added; an unchanged file gives an empty string.
+#[tauri::command]
+pub fn git_diff_file(repo_path: PathBuf, file_path: String) -> Result<String, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| format!("Failed to open repo: {}", e))?;
+    let relative = repo_relative_path(&repo_path, &file_path)?;
+
+    // No HEAD yet means everything is compared against the empty tree
+    let head_tree = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
+
+    let mut options = git2::DiffOptions::new();
+    options
+        .pathspec(&relative)
+        .disable_pathspec_match(true)
+        .include_untracked(true)
+        .show_untracked_content(true);
+    let diff = repo
+        .diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut options))
+        .map_err(|e| format!("Failed to diff: {}", e))?;
+
+    let mut patch = String::new();
+    diff.print(git2::DiffFormat::Patch, |_delta, _hunk, line| {
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            patch.push(line.origin());
+        }
+        patch.push_str(&String::from_utf8_lossy(line.content()));
+        true
+    })
+    .map_err(|e| format!("Failed to format diff: {}", e))?;
+
+    Ok(patch)
+}
+
+/// Path and status label of a status entry, with the old path for staged renames
+fn file_status(entry: &git2::StatusEntry) -> FileStatus {
+    let status = entry.status();
+    let path = entry.path().unwrap_or("").to_string();
+
+    if status.is_index_renamed() {
+        if let Some(delta) = entry.head_to_index() {
+            let new_path = delta
+                .new_file()
+                .path()
+                .map(|p| p.to_string_lossy().to_string());
+            let old_path = delta
+                .old_file()
+                .path()
+                .map(|p| p.to_string_lossy().to_string());
+            return FileStatus {
+                path: new_path.unwrap_or(path),
+                status: "renamed".to_string(),
+                old_path,
+            };
+        }
+    }
+
+    FileStatus {
+        path,
+        status: status_label(status).to_string(),
+        old_path: None,
+    }
+}
+
+/// Changed file reported by `git_status`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FileStatus {
+    pub path: String,
+    /// modified, new, deleted, renamed, typechange or conflicted
+    pub status: String,
+    /// Previous path of a renamed file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_path: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GitStatus {
     pub branch: Option<String>,
@@ -540,4 +1922,195 @@ pub struct GitStatus {
     pub deleted: usize,
     pub untracked: usize,
     pub is_clean: bool,
+    /// False on a freshly initialized repository (unborn HEAD)
+    pub has_commits: bool,
+    /// Every changed file, ignored files excluded
+    pub files: Vec<FileStatus>,
+    /// False when the branch has no upstream, in which case ahead and behind are 0
+    pub has_upstream: bool,
+    /// Local commits not yet pushed
+    pub ahead: usize,
+    /// Remote commits not yet pulled
+    pub behind: usize,
+}
+
+/// File that a sync would stage
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncFile {
+    pub path: String,
+    pub status: String,
+}
+
+/// What `git_sync` would commit
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncPreview {
+    pub files: Vec<SyncFile>,
+    pub will_commit: bool,
+    pub large_files: Vec<LargeFile>,
+}
+
+/// Preview the files `git_sync` would stage and whether it would create a commit
+/// Takes the same `force`, `large_file_threshold` and `paths` options as `git_sync`.
+/// Staging happens on an in-memory index, so nothing is written to the repository's index.
+#[tauri::command]
+pub fn git_preview_sync(
+    repo_path: PathBuf,
+    force: Option<bool>,
+    large_file_threshold: Option<u64>,
+    paths: Option<Vec<String>>,
+) -> Result<SyncPreview, String> {
+    let repo = Repository::open(&repo_path).map_err(|e| format!("Failed to open repo: {}", e))?;
+    let large_files = find_large_files(
+        &repo,
+        large_file_threshold.unwrap_or(DEFAULT_LARGE_FILE_THRESHOLD),
+    )?;
+    let skipped: Vec<String> = if force.unwrap_or(false) {
+        Vec::new()
+    } else {
+        large_files.iter().map(|f| f.path.clone()).collect()
+    };
+
+    let mut index = repo
+        .index()
+        .map_err(|e| format!("Failed to get index: {}", e))?;
+    stage_sync_changes(&repo_path, &mut index, paths.as_deref(), &skipped)?;
+    let tree = index
+        .write_tree()
+        .and_then(|id| repo.find_tree(id))
+        .map_err(|e| format!("Failed to write tree: {}", e))?;
+
+    let parent_tree = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
+    let mut diff = repo
+        .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
+        .map_err(|e| format!("Failed to diff: {}", e))?;
+    diff.find_similar(None)
+        .map_err(|e| format!("Failed to detect renames: {}", e))?;
+
+    let files: Vec<SyncFile> = diff
+        .deltas()
+        .filter_map(|delta| {
+            let path = delta
+                .new_file()
+                .path()
+                .or_else(|| delta.old_file().path())?;
+            let status = match delta.status() {
+                git2::Delta::Added | git2::Delta::Copied => "new",
+                git2::Delta::Deleted => "deleted",
+                git2::Delta::Renamed => "renamed",
+                git2::Delta::Typechange => "typechange",
+                _ => "modified",
+            };
+            Some(SyncFile {
+                path: path.to_string_lossy().to_string(),
+                status: status.to_string(),
+            })
+        })
+        .collect();
+
+    Ok(SyncPreview {
+        will_commit: !files.is_empty(),
+        files,
+        large_files,
+    })
+}
+
+/// Changed or untracked files in the working tree larger than `threshold` bytes
+fn find_large_files(repo: &Repository, threshold: u64) -> Result<Vec<LargeFile>, String> {
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| "Repository has no working directory".to_string())?;
+
+    let mut options = git2::StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false);
+
+    let statuses = repo
+        .statuses(Some(&mut options))
+        .map_err(|e| format!("Failed to get status: {}", e))?;
+
+    Ok(statuses
+        .iter()
+        .filter(|entry| entry.status().is_wt_new() || entry.status().is_wt_modified())
+        .filter_map(|entry| {
+            let path = entry.path()?.to_string();
+            let size_bytes = std::fs::metadata(workdir.join(&path)).ok()?.len();
+            (size_bytes > threshold).then_some(LargeFile { path, size_bytes })
+        })
+        .collect())
+}
+
+/// Human readable label for a file status
+fn status_label(status: git2::Status) -> &'static str {
+    if status.is_conflicted() {
+        "conflicted"
+    } else if status.is_wt_new() || status.is_index_new() {
+        "new"
+    } else if status.is_wt_deleted() || status.is_index_deleted() {
+        "deleted"
+    } else if status.is_wt_renamed() || status.is_index_renamed() {
+        "renamed"
+    } else if status.is_wt_typechange() || status.is_index_typechange() {
+        "typechange"
+    } else {
+        "modified"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commit_time_to_mtime_rejects_pre_epoch_commits() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+
+        // A commit dated 1969-12-31, e.g. imported from a VCS with broken dates
+        let time = git2::Time::new(-86_400, 0);
+        let signature = Signature::new("Test", "test@example.com", &time).unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        let oid = repo
+            .commit(Some("HEAD"), &signature, &signature, "old", &tree, &[])
+            .unwrap();
+
+        let seconds = repo.find_commit(oid).unwrap().time().seconds();
+        assert!(seconds < 0);
+        assert_eq!(commit_time_to_mtime(seconds), None);
+        assert_eq!(commit_time_to_mtime(-1), None);
+    }
+
+    #[test]
+    fn commit_time_to_mtime_accepts_epoch_and_past_times() {
+        use std::time::{Duration, SystemTime};
+
+        assert_eq!(commit_time_to_mtime(0), Some(SystemTime::UNIX_EPOCH));
+        assert_eq!(
+            commit_time_to_mtime(1_700_000_000),
+            Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+        );
+    }
+
+    #[test]
+    fn git_status_on_freshly_initialized_repo() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut options = git2::RepositoryInitOptions::new();
+        options.initial_head("main");
+        Repository::init_opts(dir.path(), &options).unwrap();
+        std::fs::write(dir.path().join("note.pn"), "hello\n").unwrap();
+
+        let status = git_status(dir.path().to_path_buf()).unwrap();
+
+        assert_eq!(status.branch.as_deref(), Some("main"));
+        assert!(!status.has_commits);
+        assert!(!status.has_upstream);
+        assert_eq!(status.untracked, 1);
+        assert_eq!((status.ahead, status.behind), (0, 0));
+        assert_eq!(status.files.len(), 1);
+        assert_eq!(status.files[0].path, "note.pn");
+        assert_eq!(status.files[0].status, "new");
+        assert!(!status.is_clean);
+    }
 }
@@ -4,8 +4,24 @@
 // 2. File listing
 // 3. Note operations
 // 4. Task aggregation
+// 5. Task reminders
+// 6. Vault analysis
+// 7. App settings
+// 8. Vault backup
+// 9. Secret scanning
+// 10. Stored credentials
+// 11. Full-text search
+// 12. Workspace watching
 
+pub mod analysis;
+pub mod backup;
+pub mod credentials;
 pub mod files;
 pub mod git;
 pub mod notes;
+pub mod reminders;
+pub mod search;
+pub mod secrets;
+pub mod settings;
 pub mod tasks;
+pub mod watcher;
@@ -1,11 +1,19 @@
 // Note operations for patto-mobile
 // Read, write, render notes using patto parser and mobile renderer
 
-use crate::renderer::MobileHtmlRenderer;
+use crate::commands::files::{
+    collect_patto_files as collect_file_entries, move_to_trash, resolve_within_root, LinkCache,
+};
+use crate::commands::tasks::{collect_patto_files, emit_tasks_changed, TaskCache};
+use crate::images::{apply_orientation, exif_orientation, image_data_url, image_mime_type};
+use crate::renderer::{html_escape, ImagePathStrategy, MobileHtmlRenderer, RenderOptions};
 use patto::parser;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tauri::{AppHandle, Manager};
 
 /// Rendered note with metadata
 #[derive(Debug, Serialize, Deserialize)]
@@ -15,50 +23,285 @@ pub struct RenderedNote {
     pub name: String,
     pub html: String,
     pub raw_content: String,
+    /// blake3 hash of the file bytes, changes whenever the note does
+    pub content_hash: String,
+}
+
+/// Raw note content with the encoding it was decoded from
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteContent {
+    pub content: String,
+    pub encoding: String,
+    /// True if invalid bytes were replaced while decoding
+    pub lossy: bool,
 }
 
 /// Read raw note content
+/// Non-UTF-8 notes are decoded via BOM sniffing, or lossily so they can still be repaired
 #[tauri::command]
-pub fn read_note(root: PathBuf, file_path: String) -> Result<String, String> {
-    let full_path = root.join(&file_path);
+pub fn read_note(
+    root: PathBuf,
+    file_path: String,
+    max_size: Option<u64>,
+) -> Result<NoteContent, String> {
+    let full_path = resolve_within_root(&root, &file_path)?;
 
     if !full_path.exists() {
         return Err(format!("File not found: {}", file_path));
     }
 
-    fs::read_to_string(&full_path).map_err(|e| format!("Failed to read file: {}", e))
+    check_note_size(&full_path, &file_path, max_size)?;
+
+    let bytes = fs::read(&full_path).map_err(|e| format!("Failed to read file: {}", e))?;
+    Ok(decode_note_bytes(&bytes))
+}
+
+/// Decode note bytes, honoring a UTF-8/UTF-16 BOM and falling back to lossy UTF-8
+pub(crate) fn decode_note_bytes(bytes: &[u8]) -> NoteContent {
+    if let Some((encoding, bom_len)) = encoding_rs::Encoding::for_bom(bytes) {
+        let (content, lossy) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+        return NoteContent {
+            content: content.into_owned(),
+            encoding: encoding.name().to_string(),
+            lossy,
+        };
+    }
+
+    match std::str::from_utf8(bytes) {
+        Ok(content) => NoteContent {
+            content: content.to_string(),
+            encoding: "UTF-8".to_string(),
+            lossy: false,
+        },
+        Err(_) => {
+            log::warn!("Note is not valid UTF-8, decoding lossily");
+            NoteContent {
+                content: String::from_utf8_lossy(bytes).into_owned(),
+                encoding: "UTF-8".to_string(),
+                lossy: true,
+            }
+        }
+    }
 }
 
 /// Write note content
+/// With `normalize_line_endings`, CRLF/CR line endings are converted to LF
+/// With `expected_mtime` (seconds, as from `get_file_info`), errors with `Conflict:`
+/// instead of overwriting a file that changed on disk since it was opened
 #[tauri::command]
-pub fn write_note(root: PathBuf, file_path: String, content: String) -> Result<(), String> {
-    let full_path = root.join(&file_path);
+pub fn write_note(
+    app: AppHandle,
+    root: PathBuf,
+    file_path: String,
+    content: String,
+    normalize_line_endings: Option<bool>,
+    expected_mtime: Option<u64>,
+) -> Result<(), String> {
+    let full_path = resolve_within_root(&root, &file_path)?;
+    let content = if normalize_line_endings.unwrap_or(false) {
+        to_lf(&content)
+    } else {
+        content
+    };
 
     // Ensure parent directory exists
     if let Some(parent) = full_path.parent() {
         fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
     }
 
-    fs::write(&full_path, content).map_err(|e| format!("Failed to write file: {}", e))
+    // Files on synced storage can be read-only or temporarily locked,
+    // report those distinctly so the UI can ask the user to retry
+    let metadata = fs::metadata(&full_path).ok();
+    if metadata
+        .as_ref()
+        .is_some_and(|m| m.permissions().readonly())
+    {
+        return Err(format!("ReadOnly: {} is read-only", file_path));
+    }
+
+    // A pull or another app may have rewritten (or removed) the file meanwhile
+    if let Some(expected) = expected_mtime {
+        let modified = metadata
+            .and_then(|m| m.modified().ok())
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs());
+        if modified != Some(expected) {
+            return Err(format!(
+                "Conflict: {} changed on disk since it was opened",
+                file_path
+            ));
+        }
+    }
+
+    fs::write(&full_path, content).map_err(|e| write_error_message(&e, &file_path))?;
+
+    // Edits may have added or removed links and tasks
+    invalidate_note_caches(&app, &full_path);
+
+    // Edits may have added, removed or completed tasks
+    emit_tasks_changed(&app, &file_path);
+
+    Ok(())
+}
+
+/// Append `content` to a note, creating it (and parent directories) if missing
+/// A newline is inserted first unless the note is empty or already ends with one.
+/// Returns the new size in bytes.
+#[tauri::command]
+pub fn append_to_note(
+    app: AppHandle,
+    root: PathBuf,
+    file_path: String,
+    content: String,
+) -> Result<u64, String> {
+    use std::io::{Read, Seek, SeekFrom, Write};
+
+    let full_path = resolve_within_root(&root, &file_path)?;
+
+    if let Some(parent) = full_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    let mut file = fs::OpenOptions::new()
+        .read(true)
+        .append(true)
+        .create(true)
+        .open(&full_path)
+        .map_err(|e| write_error_message(&e, &file_path))?;
+
+    let len = file
+        .metadata()
+        .map_err(|e| format!("Failed to read file: {}", e))?
+        .len();
+    let mut last = [0u8; 1];
+    if len > 0 {
+        file.seek(SeekFrom::End(-1))
+            .and_then(|_| file.read_exact(&mut last))
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+    }
+
+    let mut bytes = Vec::with_capacity(content.len() + 1);
+    if len > 0 && last[0] != b'\n' {
+        bytes.push(b'\n');
+    }
+    bytes.extend_from_slice(content.as_bytes());
+
+    // Append mode writes at the end regardless of the read position
+    file.write_all(&bytes)
+        .map_err(|e| write_error_message(&e, &file_path))?;
+
+    // The appended text may add links or tasks
+    invalidate_note_caches(&app, &full_path);
+    emit_tasks_changed(&app, &file_path);
+
+    Ok(len + bytes.len() as u64)
+}
+
+/// Forget the cached links and tasks of a note that was just rewritten
+/// Mtimes can be too coarse to notice an edit made right after the last scan
+fn invalidate_note_caches(app: &AppHandle, full_path: &Path) {
+    invalidate_cached_note(
+        &app.state::<LinkCache>(),
+        &app.state::<TaskCache>(),
+        full_path,
+    );
+}
+
+fn invalidate_cached_note(links: &LinkCache, tasks: &TaskCache, full_path: &Path) {
+    links.invalidate(full_path);
+    tasks.invalidate(full_path);
+}
+
+/// Convert CRLF and lone CR line endings to LF
+fn to_lf(content: &str) -> String {
+    content.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/// Convert line endings of every note in the workspace to LF
+/// Returns the relative paths of the notes that were rewritten
+#[tauri::command]
+pub async fn normalize_workspace_line_endings(
+    app: AppHandle,
+    root: PathBuf,
+) -> Result<Vec<String>, String> {
+    tokio::task::spawn_blocking(move || {
+        let files = collect_patto_files(&root).map_err(|e| e.to_string())?;
+        let mut changed = Vec::new();
+
+        for file_path in files {
+            let full_path = root.join(&file_path);
+            let content = match fs::read_to_string(&full_path) {
+                Ok(content) => content,
+                Err(_) => continue,
+            };
+
+            if !content.contains('\r') {
+                continue;
+            }
+
+            fs::write(&full_path, to_lf(&content))
+                .map_err(|e| write_error_message(&e, &file_path))?;
+            invalidate_note_caches(&app, &full_path);
+            changed.push(file_path);
+        }
+
+        Ok(changed)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Map a write error to a `ReadOnly:`/`Locked:` prefixed message when possible
+fn write_error_message(e: &std::io::Error, file_path: &str) -> String {
+    #[cfg(unix)]
+    const READ_ONLY_CODES: &[i32] = &[30]; // EROFS
+    #[cfg(unix)]
+    const LOCKED_CODES: &[i32] = &[16, 26]; // EBUSY, ETXTBSY
+    #[cfg(windows)]
+    const READ_ONLY_CODES: &[i32] = &[19]; // ERROR_WRITE_PROTECT
+    #[cfg(windows)]
+    const LOCKED_CODES: &[i32] = &[32, 33]; // ERROR_SHARING_VIOLATION, ERROR_LOCK_VIOLATION
+
+    match e.raw_os_error() {
+        Some(code) if READ_ONLY_CODES.contains(&code) => {
+            format!("ReadOnly: {} is on a read-only filesystem", file_path)
+        }
+        Some(code) if LOCKED_CODES.contains(&code) => {
+            format!("Locked: {} is locked by another process", file_path)
+        }
+        _ if e.kind() == std::io::ErrorKind::PermissionDenied => {
+            format!("ReadOnly: {} is not writable", file_path)
+        }
+        _ => format!("Failed to write file: {}", e),
+    }
 }
 
 /// Render note to HTML using mobile-optimized renderer
 #[tauri::command]
-pub fn render_note(root: PathBuf, file_path: String) -> Result<RenderedNote, String> {
-    let full_path = root.join(&file_path);
+pub fn render_note(
+    root: PathBuf,
+    file_path: String,
+    options: Option<RenderOptions>,
+    max_size: Option<u64>,
+) -> Result<RenderedNote, String> {
+    let full_path = resolve_within_root(&root, &file_path)?;
 
     if !full_path.exists() {
         return Err(format!("File not found: {}", file_path));
     }
 
-    let content =
-        fs::read_to_string(&full_path).map_err(|e| format!("Failed to read file: {}", e))?;
+    check_note_size(&full_path, &file_path, max_size)?;
+
+    let bytes = fs::read(&full_path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let content = decode_note_bytes(&bytes).content;
 
     // Parse the content
     let parse_result = parser::parse_text(&content);
 
     // Render to HTML using mobile renderer
-    let renderer = MobileHtmlRenderer::new(Some(root.to_string_lossy().to_string()));
+    let renderer = MobileHtmlRenderer::new(Some(root.to_string_lossy().to_string()))
+        .with_options(options.unwrap_or_default());
     let html = renderer
         .render(&parse_result.ast)
         .map_err(|e| format!("Failed to render: {}", e))?;
@@ -74,22 +317,629 @@ pub fn render_note(root: PathBuf, file_path: String) -> Result<RenderedNote, Str
         name,
         html,
         raw_content: content,
+        content_hash: content_hash(&bytes),
     })
 }
 
+/// Stable hex hash of file contents for cheap change detection
+pub(crate) fn content_hash(bytes: &[u8]) -> String {
+    blake3::hash(bytes).to_hex().to_string()
+}
+
+/// Options for exporting a note as a standalone HTML document
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExportOptions {
+    /// Custom stylesheet, replaces the default one
+    pub stylesheet: Option<String>,
+    /// Document title, defaults to the note name
+    pub title: Option<String>,
+}
+
+/// Default stylesheet for exported documents
+const EXPORT_STYLESHEET: &str = include_str!("../export.css");
+
+/// Export a note as a complete HTML document with embedded CSS and inlined images
+#[tauri::command]
+pub fn export_html_document(
+    root: PathBuf,
+    file_path: String,
+    options: Option<ExportOptions>,
+) -> Result<String, String> {
+    let options = options.unwrap_or_default();
+
+    let rendered = render_note(
+        root,
+        file_path,
+        Some(RenderOptions {
+            image_strategy: ImagePathStrategy::Base64Inline,
+            ..Default::default()
+        }),
+        None,
+    )?;
+
+    let title = options.title.unwrap_or(rendered.name);
+    let stylesheet = options.stylesheet.as_deref().unwrap_or(EXPORT_STYLESHEET);
+
+    Ok(format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\"/>\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\"/>\n\
+         <title>{}</title>\n<style>\n{}\n</style>\n</head>\n<body>\n{}\n</body>\n</html>\n",
+        html_escape(&title),
+        stylesheet,
+        rendered.html
+    ))
+}
+
+/// Render note on a background thread so a slow parse doesn't block the command thread
+/// The frontend can stop awaiting the result to treat it as cancelled
+#[tauri::command]
+pub async fn render_note_async(
+    root: PathBuf,
+    file_path: String,
+    options: Option<RenderOptions>,
+    max_size: Option<u64>,
+) -> Result<RenderedNote, String> {
+    tokio::task::spawn_blocking(move || render_note(root, file_path, options, max_size))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Default maximum note size that will be parsed (4 MB)
+const DEFAULT_MAX_NOTE_SIZE: u64 = 4 * 1024 * 1024;
+
+/// Refuse to load notes larger than `max_size` (or the default limit)
+pub(crate) fn check_note_size(
+    full_path: &Path,
+    file_path: &str,
+    max_size: Option<u64>,
+) -> Result<(), String> {
+    let limit = max_size.unwrap_or(DEFAULT_MAX_NOTE_SIZE);
+    let size = fs::metadata(full_path)
+        .map_err(|e| format!("Failed to read file: {}", e))?
+        .len();
+
+    if size > limit {
+        return Err(format!(
+            "TooLarge: {} is {} bytes (limit {} bytes)",
+            file_path, size, limit
+        ));
+    }
+
+    Ok(())
+}
+
+/// Single line of a diff hunk
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    pub tag: String, // "equal", "delete" or "insert"
+    pub content: String,
+}
+
+/// Contiguous group of changes with surrounding context
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+/// Line-level diff of an editor buffer against the note on disk (independent of git)
+#[tauri::command]
+pub fn diff_against_disk(
+    root: PathBuf,
+    file_path: String,
+    buffer: String,
+) -> Result<Vec<Hunk>, String> {
+    use similar::{ChangeTag, TextDiff};
+
+    let full_path = resolve_within_root(&root, &file_path)?;
+
+    // A note that doesn't exist yet diffs as all-new
+    let on_disk = if full_path.exists() {
+        fs::read_to_string(&full_path).map_err(|e| format!("Failed to read file: {}", e))?
+    } else {
+        String::new()
+    };
+
+    let diff = TextDiff::from_lines(&on_disk, &buffer);
+
+    Ok(diff
+        .grouped_ops(3)
+        .iter()
+        .filter_map(|group| {
+            let (first, last) = (group.first()?, group.last()?);
+            let old_start = first.old_range().start;
+            let new_start = first.new_range().start;
+
+            let lines = group
+                .iter()
+                .flat_map(|op| diff.iter_changes(op))
+                .map(|change| DiffLine {
+                    tag: match change.tag() {
+                        ChangeTag::Equal => "equal",
+                        ChangeTag::Delete => "delete",
+                        ChangeTag::Insert => "insert",
+                    }
+                    .to_string(),
+                    content: change.value().trim_end_matches(['\r', '\n']).to_string(),
+                })
+                .collect();
+
+            Some(Hunk {
+                old_start: old_start + 1,
+                old_lines: last.old_range().end - old_start,
+                new_start: new_start + 1,
+                new_lines: last.new_range().end - new_start,
+                lines,
+            })
+        })
+        .collect())
+}
+
+/// How `get_random_note` picks a note
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum Weighting {
+    #[default]
+    Uniform,
+    /// Favor notes that haven't been modified for a long time
+    LeastRecentlyModified,
+}
+
+/// Pick a random note from the workspace and render it
+#[tauri::command]
+pub fn get_random_note(
+    root: PathBuf,
+    weight_by: Option<Weighting>,
+) -> Result<RenderedNote, String> {
+    use rand::distributions::WeightedIndex;
+    use rand::prelude::*;
+
+    let entries = collect_file_entries(&root).map_err(|e| e.to_string())?;
+    if entries.is_empty() {
+        return Err("No notes in workspace".to_string());
+    }
+
+    let mut rng = rand::thread_rng();
+    let index = match weight_by.unwrap_or_default() {
+        Weighting::Uniform => rng.gen_range(0..entries.len()),
+        Weighting::LeastRecentlyModified => {
+            // Weight by age in days so stale notes resurface more often
+            let now = std::time::SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            let weights = entries
+                .iter()
+                .map(|e| now.saturating_sub(e.modified_time) / 86400 + 1);
+            WeightedIndex::new(weights)
+                .map_err(|e| format!("Failed to weight notes: {}", e))?
+                .sample(&mut rng)
+        }
+    };
+
+    render_note(root, entries[index].path.clone(), None, None)
+}
+
 /// Render content without reading from file (for preview while editing)
 #[tauri::command]
-pub fn render_content(content: String) -> Result<String, String> {
+pub fn render_content(content: String, options: Option<RenderOptions>) -> Result<String, String> {
     // Parse the content
     let parse_result = parser::parse_text(&content);
 
     // Render to HTML using mobile renderer
-    let renderer = MobileHtmlRenderer::new(None);
+    let renderer = MobileHtmlRenderer::new(None).with_options(options.unwrap_or_default());
     renderer
         .render(&parse_result.ast)
         .map_err(|e| format!("Failed to render: {}", e))
 }
 
+/// Flatten a note into an indented plain-text outline (two spaces per level)
+/// Decorations are stripped; task markers are kept as `[ ]`/`[x]`
+#[tauri::command]
+pub fn export_outline_text(root: PathBuf, file_path: String) -> Result<String, String> {
+    let full_path = resolve_within_root(&root, &file_path)?;
+
+    if !full_path.exists() {
+        return Err(format!("File not found: {}", file_path));
+    }
+
+    let content =
+        fs::read_to_string(&full_path).map_err(|e| format!("Failed to read file: {}", e))?;
+
+    let parse_result = parser::parse_text(&content);
+
+    let mut lines = Vec::new();
+    outline_lines(&parse_result.ast, 0, &mut lines);
+
+    Ok(lines.join("\n"))
+}
+
+fn outline_lines(node: &parser::AstNode, depth: usize, lines: &mut Vec<String>) {
+    use parser::{AstNodeKind, Property, TaskStatus};
+
+    let indent = "  ".repeat(depth);
+    match node.kind() {
+        AstNodeKind::Line { properties } | AstNodeKind::QuoteContent { properties } => {
+            let marker = properties.iter().find_map(|prop| match prop {
+                Property::Task { status, .. } => Some(match status {
+                    TaskStatus::Done => "[x] ",
+                    TaskStatus::Todo | TaskStatus::Doing => "[ ] ",
+                }),
+                _ => None,
+            });
+
+            let mut text = String::new();
+            for content in node.value().contents.lock().unwrap().iter() {
+                outline_inline_text(content, &mut text);
+            }
+            let text = text.trim();
+
+            // Lines holding only a block (code, table, ...) have no text of their own
+            if !text.is_empty() || marker.is_some() {
+                lines.push(format!("{}- {}{}", indent, marker.unwrap_or(""), text));
+            }
+
+            for content in node.value().contents.lock().unwrap().iter() {
+                if !is_inline(content) {
+                    outline_lines(content, depth + 1, lines);
+                }
+            }
+            for child in node.value().children.lock().unwrap().iter() {
+                outline_lines(child, depth + 1, lines);
+            }
+        }
+        AstNodeKind::Code { inline: false, .. } | AstNodeKind::Math { inline: false } => {
+            for child in node.value().children.lock().unwrap().iter() {
+                lines.push(format!("{}{}", indent, child.extract_str()));
+            }
+        }
+        AstNodeKind::TableRow => {
+            let columns: Vec<String> = node
+                .value()
+                .contents
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|column| {
+                    let mut text = String::new();
+                    outline_inline_text(column, &mut text);
+                    text.trim().to_string()
+                })
+                .collect();
+            lines.push(format!("{}- {}", indent, columns.join(" | ")));
+        }
+        _ => {
+            // Dummy root, quotes and tables: children stay at the current depth
+            for child in node.value().children.lock().unwrap().iter() {
+                outline_lines(child, depth, lines);
+            }
+        }
+    }
+}
+
+fn is_inline(node: &parser::AstNode) -> bool {
+    use parser::AstNodeKind;
+
+    match node.kind() {
+        AstNodeKind::Code { inline, .. } | AstNodeKind::Math { inline } => *inline,
+        AstNodeKind::Table { .. } | AstNodeKind::Quote => false,
+        _ => true,
+    }
+}
+
+fn outline_inline_text(node: &parser::AstNode, text: &mut String) {
+    use parser::AstNodeKind;
+
+    match node.kind() {
+        AstNodeKind::WikiLink { link, anchor } => match anchor {
+            Some(anchor) if link.is_empty() => text.push_str(&format!("#{}", anchor)),
+            Some(anchor) => text.push_str(&format!("{}#{}", link, anchor)),
+            None => text.push_str(link),
+        },
+        AstNodeKind::Link { link, title } => text.push_str(title.as_deref().unwrap_or(link)),
+        AstNodeKind::Image { src, alt } => text.push_str(alt.as_deref().unwrap_or(src)),
+        AstNodeKind::HorizontalLine => text.push_str("---"),
+        AstNodeKind::Text | AstNodeKind::CodeContent | AstNodeKind::MathContent => {
+            text.push_str(node.extract_str())
+        }
+        _ if !is_inline(node) => {}
+        _ => {
+            // Decorations, inline code/math, table columns: keep the inner text only
+            for content in node.value().contents.lock().unwrap().iter() {
+                outline_inline_text(content, text);
+            }
+        }
+    }
+}
+
+/// Number of source lines rendered by `get_note_preview`
+const PREVIEW_LINES: usize = 5;
+
+/// Short preview of a note for list cells
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotePreview {
+    pub html_preview: String,
+    pub text_summary: String,
+    /// Source of the first image in the note, as written
+    pub first_image: Option<String>,
+}
+
+/// Render the first few lines, summarize the text (up to `max_chars`) and find the first image
+#[tauri::command]
+pub fn get_note_preview(
+    root: PathBuf,
+    file_path: String,
+    max_chars: usize,
+) -> Result<NotePreview, String> {
+    let full_path = resolve_within_root(&root, &file_path)?;
+
+    if !full_path.exists() {
+        return Err(format!("File not found: {}", file_path));
+    }
+
+    check_note_size(&full_path, &file_path, None)?;
+
+    let content =
+        fs::read_to_string(&full_path).map_err(|e| format!("Failed to read file: {}", e))?;
+
+    let head: Vec<&str> = content.lines().take(PREVIEW_LINES).collect();
+    let head_ast = parser::parse_text(&head.join("\n")).ast;
+    let renderer = MobileHtmlRenderer::new(Some(root.to_string_lossy().to_string()));
+    let html_preview = renderer
+        .render(&head_ast)
+        .map_err(|e| format!("Failed to render: {}", e))?;
+
+    let parse_result = parser::parse_text(&content);
+
+    let mut lines = Vec::new();
+    outline_lines(&parse_result.ast, 0, &mut lines);
+    let summary = lines
+        .iter()
+        .map(|line| {
+            let line = line.trim_start().trim_start_matches("- ");
+            line.strip_prefix("[ ] ")
+                .or_else(|| line.strip_prefix("[x] "))
+                .unwrap_or(line)
+        })
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut text_summary: String = summary.chars().take(max_chars).collect();
+    if text_summary.len() < summary.len() {
+        text_summary.push('…');
+    }
+
+    Ok(NotePreview {
+        html_preview,
+        text_summary,
+        first_image: find_first_image(&parse_result.ast),
+    })
+}
+
+fn find_first_image(node: &parser::AstNode) -> Option<String> {
+    if let parser::AstNodeKind::Image { src, .. } = node.kind() {
+        return Some(src.clone());
+    }
+
+    let contents = node.value().contents.lock().unwrap();
+    let children = node.value().children.lock().unwrap();
+    contents
+        .iter()
+        .chain(children.iter())
+        .find_map(find_first_image)
+}
+
+/// Serializable mirror of a patto `AstNode`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AstNodeJson {
+    pub kind: AstKindJson,
+    pub line_number: usize,
+    /// Raw text of leaf nodes (text, code and math content)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    pub contents: Vec<AstNodeJson>,
+    pub children: Vec<AstNodeJson>,
+}
+
+/// Serializable mirror of `AstNodeKind`, tagged by `type`
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum AstKindJson {
+    Dummy,
+    Line {
+        properties: Vec<PropertyJson>,
+    },
+    Quote,
+    QuoteContent {
+        properties: Vec<PropertyJson>,
+    },
+    Code {
+        lang: String,
+        inline: bool,
+    },
+    CodeContent,
+    Math {
+        inline: bool,
+    },
+    MathContent,
+    Image {
+        src: String,
+        alt: Option<String>,
+    },
+    WikiLink {
+        link: String,
+        anchor: Option<String>,
+    },
+    Link {
+        link: String,
+        title: Option<String>,
+    },
+    Decoration {
+        fontsize: i64,
+        italic: bool,
+        underline: bool,
+        deleted: bool,
+    },
+    Text,
+    HorizontalLine,
+    Table {
+        caption: Option<String>,
+    },
+    TableRow,
+    TableColumn,
+}
+
+/// Serializable mirror of a line `Property`
+#[derive(Debug, Serialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum PropertyJson {
+    Task {
+        status: String,
+        due: String,
+        line_number: usize,
+    },
+    Anchor {
+        name: String,
+        line_number: usize,
+    },
+}
+
+/// Parse a note and return its syntax tree as JSON (for editor tooling and plugins)
+#[tauri::command]
+pub fn parse_note_ast(root: PathBuf, file_path: String) -> Result<serde_json::Value, String> {
+    let full_path = resolve_within_root(&root, &file_path)?;
+
+    if !full_path.exists() {
+        return Err(format!("File not found: {}", file_path));
+    }
+
+    let content =
+        fs::read_to_string(&full_path).map_err(|e| format!("Failed to read file: {}", e))?;
+
+    let parse_result = parser::parse_text(&content);
+
+    serde_json::to_value(ast_to_json(&parse_result.ast))
+        .map_err(|e| format!("Failed to serialize AST: {}", e))
+}
+
+fn ast_to_json(node: &parser::AstNode) -> AstNodeJson {
+    use parser::AstNodeKind;
+
+    let kind = match node.kind() {
+        AstNodeKind::Dummy => AstKindJson::Dummy,
+        AstNodeKind::Line { properties } => AstKindJson::Line {
+            properties: properties.iter().map(property_to_json).collect(),
+        },
+        AstNodeKind::Quote => AstKindJson::Quote,
+        AstNodeKind::QuoteContent { properties } => AstKindJson::QuoteContent {
+            properties: properties.iter().map(property_to_json).collect(),
+        },
+        AstNodeKind::Code { lang, inline } => AstKindJson::Code {
+            lang: lang.clone(),
+            inline: *inline,
+        },
+        AstNodeKind::CodeContent => AstKindJson::CodeContent,
+        AstNodeKind::Math { inline } => AstKindJson::Math { inline: *inline },
+        AstNodeKind::MathContent => AstKindJson::MathContent,
+        AstNodeKind::Image { src, alt } => AstKindJson::Image {
+            src: src.clone(),
+            alt: alt.clone(),
+        },
+        AstNodeKind::WikiLink { link, anchor } => AstKindJson::WikiLink {
+            link: link.clone(),
+            anchor: anchor.clone(),
+        },
+        AstNodeKind::Link { link, title } => AstKindJson::Link {
+            link: link.clone(),
+            title: title.clone(),
+        },
+        AstNodeKind::Decoration {
+            fontsize,
+            italic,
+            underline,
+            deleted,
+        } => AstKindJson::Decoration {
+            fontsize: *fontsize as i64,
+            italic: *italic,
+            underline: *underline,
+            deleted: *deleted,
+        },
+        AstNodeKind::Text => AstKindJson::Text,
+        AstNodeKind::HorizontalLine => AstKindJson::HorizontalLine,
+        AstNodeKind::Table { caption } => AstKindJson::Table {
+            caption: caption.clone(),
+        },
+        AstNodeKind::TableRow => AstKindJson::TableRow,
+        AstNodeKind::TableColumn => AstKindJson::TableColumn,
+    };
+
+    let text = match node.kind() {
+        AstNodeKind::Text | AstNodeKind::CodeContent | AstNodeKind::MathContent => {
+            Some(node.extract_str().to_string())
+        }
+        _ => None,
+    };
+
+    AstNodeJson {
+        kind,
+        line_number: node.location().row,
+        text,
+        contents: node
+            .value()
+            .contents
+            .lock()
+            .unwrap()
+            .iter()
+            .map(ast_to_json)
+            .collect(),
+        children: node
+            .value()
+            .children
+            .lock()
+            .unwrap()
+            .iter()
+            .map(ast_to_json)
+            .collect(),
+    }
+}
+
+fn property_to_json(property: &parser::Property) -> PropertyJson {
+    use parser::{Property, TaskStatus};
+
+    match property {
+        Property::Task {
+            status,
+            due,
+            location,
+        } => PropertyJson::Task {
+            status: match status {
+                TaskStatus::Todo => "todo",
+                TaskStatus::Doing => "doing",
+                TaskStatus::Done => "done",
+            }
+            .to_string(),
+            due: due.to_string(),
+            line_number: location.row,
+        },
+        Property::Anchor { name, location } => PropertyJson::Anchor {
+            name: name.clone(),
+            line_number: location.row,
+        },
+    }
+}
+
 /// Link information extracted from a note
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -98,12 +948,18 @@ pub struct LinkInfo {
     pub anchor: Option<String>,       // Optional anchor within target
     pub is_external: bool,            // True if URL, false if internal note link
     pub display_text: Option<String>, // Display text if different from target
+    pub occurrence_count: usize,      // Times the link appears (1 unless deduplicated)
 }
 
 /// Extract all links from a note
+/// With `unique`, duplicate links are collapsed and counted in `occurrence_count`
 #[tauri::command]
-pub fn extract_links(root: PathBuf, file_path: String) -> Result<Vec<LinkInfo>, String> {
-    let full_path = root.join(&file_path);
+pub fn extract_links(
+    root: PathBuf,
+    file_path: String,
+    unique: Option<bool>,
+) -> Result<Vec<LinkInfo>, String> {
+    let full_path = resolve_within_root(&root, &file_path)?;
 
     if !full_path.exists() {
         return Err(format!("File not found: {}", file_path));
@@ -118,10 +974,30 @@ pub fn extract_links(root: PathBuf, file_path: String) -> Result<Vec<LinkInfo>,
     let mut links = Vec::new();
     extract_links_from_ast(&parse_result.ast, &mut links);
 
+    if unique.unwrap_or(false) {
+        links = dedup_links(links);
+    }
+
     Ok(links)
 }
 
-fn extract_links_from_ast(node: &parser::AstNode, links: &mut Vec<LinkInfo>) {
+/// Collapse duplicate links by (target, anchor, is_external), keeping first-seen order
+fn dedup_links(links: Vec<LinkInfo>) -> Vec<LinkInfo> {
+    let mut unique: Vec<LinkInfo> = Vec::new();
+
+    for link in links {
+        match unique.iter_mut().find(|l| {
+            l.target == link.target && l.anchor == link.anchor && l.is_external == link.is_external
+        }) {
+            Some(existing) => existing.occurrence_count += 1,
+            None => unique.push(link),
+        }
+    }
+
+    unique
+}
+
+pub(crate) fn extract_links_from_ast(node: &parser::AstNode, links: &mut Vec<LinkInfo>) {
     use parser::AstNodeKind;
 
     match node.kind() {
@@ -131,6 +1007,7 @@ fn extract_links_from_ast(node: &parser::AstNode, links: &mut Vec<LinkInfo>) {
                 anchor: anchor.clone(),
                 is_external: false,
                 display_text: None,
+                occurrence_count: 1,
             });
         }
         AstNodeKind::Link { link, title } => {
@@ -139,6 +1016,7 @@ fn extract_links_from_ast(node: &parser::AstNode, links: &mut Vec<LinkInfo>) {
                 anchor: None,
                 is_external: true,
                 display_text: title.clone(),
+                occurrence_count: 1,
             });
         }
         _ => {}
@@ -154,28 +1032,699 @@ fn extract_links_from_ast(node: &parser::AstNode, links: &mut Vec<LinkInfo>) {
     }
 }
 
+/// Wikilink target that has no note yet
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingTarget {
+    pub target: String,
+    pub reference_count: usize,
+    pub referenced_from: Vec<String>,
+}
+
+/// List wikilink targets across the workspace that have no corresponding note
+#[tauri::command]
+pub async fn list_missing_note_targets(root: PathBuf) -> Result<Vec<MissingTarget>, String> {
+    tokio::task::spawn_blocking(move || {
+        let files = collect_patto_files(&root).map_err(|e| e.to_string())?;
+        let mut missing: HashMap<String, MissingTarget> = HashMap::new();
+
+        for file_path in files {
+            let content = match fs::read_to_string(root.join(&file_path)) {
+                Ok(content) => content,
+                Err(_) => continue,
+            };
+
+            let parse_result = parser::parse_text(&content);
+            let mut links = Vec::new();
+            extract_links_from_ast(&parse_result.ast, &mut links);
+
+            for link in links {
+                if link.is_external || link.target.is_empty() {
+                    continue;
+                }
+                if note_path(&root, &link.target).exists() {
+                    continue;
+                }
+
+                let entry = missing
+                    .entry(link.target.clone())
+                    .or_insert_with(|| MissingTarget {
+                        target: link.target.clone(),
+                        reference_count: 0,
+                        referenced_from: Vec::new(),
+                    });
+                entry.reference_count += 1;
+                if !entry.referenced_from.contains(&file_path) {
+                    entry.referenced_from.push(file_path.clone());
+                }
+            }
+        }
+
+        // Most referenced first
+        let mut missing: Vec<MissingTarget> = missing.into_values().collect();
+        missing.sort_by(|a, b| {
+            b.reference_count
+                .cmp(&a.reference_count)
+                .then_with(|| a.target.cmp(&b.target))
+        });
+
+        Ok(missing)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Create empty notes for the given wikilink targets
+/// Returns the relative paths of the notes that were created
+#[tauri::command]
+pub fn create_stub_notes(root: PathBuf, targets: Vec<String>) -> Result<Vec<String>, String> {
+    let mut created = Vec::new();
+
+    for target in targets {
+        let full_path = resolve_within_root(&root, &format!("{}.pn", target))?;
+        if full_path.exists() {
+            continue;
+        }
+
+        if let Some(parent) = full_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+        }
+
+        fs::write(&full_path, "").map_err(|e| format!("Failed to create file: {}", e))?;
+        created.push(format!("{}.pn", target));
+    }
+
+    Ok(created)
+}
+
+/// Kind of reference found in a note
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReferenceKind {
+    Internal,  // Wikilink to another note
+    IntraNote, // Anchor-only wikilink within the same note
+    External,  // URL
+    Asset,     // Image source
+}
+
+/// Reference (link or image) with its position in the note
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReferenceInfo {
+    pub kind: ReferenceKind,
+    pub target: String,
+    pub anchor: Option<String>,
+    pub display_text: Option<String>,
+    pub line_number: usize,
+}
+
+/// Extract all links and image references from a note
+#[tauri::command]
+pub fn extract_references(root: PathBuf, file_path: String) -> Result<Vec<ReferenceInfo>, String> {
+    let full_path = resolve_within_root(&root, &file_path)?;
+
+    if !full_path.exists() {
+        return Err(format!("File not found: {}", file_path));
+    }
+
+    let content =
+        fs::read_to_string(&full_path).map_err(|e| format!("Failed to read file: {}", e))?;
+
+    // Parse the content
+    let parse_result = parser::parse_text(&content);
+
+    let mut references = Vec::new();
+    extract_references_from_ast(&parse_result.ast, &mut references);
+
+    Ok(references)
+}
+
+fn extract_references_from_ast(node: &parser::AstNode, references: &mut Vec<ReferenceInfo>) {
+    use parser::AstNodeKind;
+
+    let line_number = node.location().row;
+    match node.kind() {
+        AstNodeKind::WikiLink { link, anchor } => {
+            references.push(ReferenceInfo {
+                kind: if link.is_empty() {
+                    ReferenceKind::IntraNote
+                } else {
+                    ReferenceKind::Internal
+                },
+                target: link.clone(),
+                anchor: anchor.clone(),
+                display_text: None,
+                line_number,
+            });
+        }
+        AstNodeKind::Link { link, title } => {
+            references.push(ReferenceInfo {
+                kind: ReferenceKind::External,
+                target: link.clone(),
+                anchor: None,
+                display_text: title.clone(),
+                line_number,
+            });
+        }
+        AstNodeKind::Image { src, alt } => {
+            references.push(ReferenceInfo {
+                kind: ReferenceKind::Asset,
+                target: src.clone(),
+                anchor: None,
+                display_text: alt.clone(),
+                line_number,
+            });
+        }
+        _ => {}
+    }
+
+    // Recurse into contents
+    for child in node.value().contents.lock().unwrap().iter() {
+        extract_references_from_ast(child, references);
+    }
+    // Recurse into children
+    for child in node.value().children.lock().unwrap().iter() {
+        extract_references_from_ast(child, references);
+    }
+}
+
+/// Result of checking a single link
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LinkHealth {
+    Ok,
+    MissingFile,
+    MissingAnchor,
+}
+
+/// Link of a note with its health
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkStatus {
+    pub target: String,
+    pub anchor: Option<String>,
+    pub kind: ReferenceKind,
+    pub status: LinkHealth,
+    pub line_number: usize,
+}
+
+/// Check which links of a note are valid, broken or external
+#[tauri::command]
+pub fn analyze_note_links(root: PathBuf, file_path: String) -> Result<Vec<LinkStatus>, String> {
+    let references = extract_references(root.clone(), file_path.clone())?;
+
+    // Anchors per target note, parsed at most once
+    let mut anchor_cache: HashMap<PathBuf, Vec<String>> = HashMap::new();
+
+    Ok(references
+        .into_iter()
+        .map(|reference| {
+            let status = match reference.kind {
+                ReferenceKind::External => LinkHealth::Ok,
+                ReferenceKind::Asset => {
+                    let src = &reference.target;
+                    // Paths outside the workspace are reported missing rather than probed
+                    if src.starts_with("http://")
+                        || src.starts_with("https://")
+                        || resolve_within_root(&root, src).is_ok_and(|path| path.exists())
+                    {
+                        LinkHealth::Ok
+                    } else {
+                        LinkHealth::MissingFile
+                    }
+                }
+                ReferenceKind::Internal | ReferenceKind::IntraNote => {
+                    let target_path = if reference.kind == ReferenceKind::IntraNote {
+                        root.join(&file_path)
+                    } else {
+                        note_path(&root, &reference.target)
+                    };
+
+                    if !target_path.exists() {
+                        LinkHealth::MissingFile
+                    } else if let Some(anchor) = &reference.anchor {
+                        let anchors = anchor_cache
+                            .entry(target_path.clone())
+                            .or_insert_with(|| note_anchor_names(&target_path));
+                        if anchors.contains(anchor) {
+                            LinkHealth::Ok
+                        } else {
+                            LinkHealth::MissingAnchor
+                        }
+                    } else {
+                        LinkHealth::Ok
+                    }
+                }
+            };
+
+            LinkStatus {
+                target: reference.target,
+                anchor: reference.anchor,
+                kind: reference.kind,
+                status,
+                line_number: reference.line_number,
+            }
+        })
+        .collect())
+}
+
+/// Names of all anchors defined in a note file
+fn note_anchor_names(path: &Path) -> Vec<String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => return Vec::new(),
+    };
+
+    let parse_result = parser::parse_text(&content);
+    let mut anchors = Vec::new();
+    extract_anchors_from_ast(&parse_result.ast, &mut anchors);
+
+    anchors.into_iter().map(|a| a.name).collect()
+}
+
+/// Lint severity
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LintSeverity {
+    Error,
+    Warning,
+}
+
+/// Issue found while validating a note
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Lint {
+    pub severity: LintSeverity,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+/// Validate note content before saving
+/// Reports parser errors, empty wikilinks and wikilinks to missing notes
+#[tauri::command]
+pub fn validate_note(root: PathBuf, content: String) -> Result<Vec<Lint>, String> {
+    let parse_result = parser::parse_text(&content);
+
+    let mut lints: Vec<Lint> = parse_result
+        .parse_errors
+        .iter()
+        .map(|e| Lint {
+            severity: LintSeverity::Error,
+            line: None,
+            message: e.to_string(),
+        })
+        .collect();
+
+    lint_links_in_ast(&parse_result.ast, &root, &mut lints);
+
+    Ok(lints)
+}
+
+fn lint_links_in_ast(node: &parser::AstNode, root: &Path, lints: &mut Vec<Lint>) {
+    use parser::AstNodeKind;
+
+    if let AstNodeKind::WikiLink { link, anchor } = node.kind() {
+        let line = Some(node.location().row);
+        if link.is_empty() && anchor.as_deref().unwrap_or("").is_empty() {
+            lints.push(Lint {
+                severity: LintSeverity::Warning,
+                line,
+                message: "Empty wikilink".to_string(),
+            });
+        } else if !link.is_empty() && !note_path(root, link).exists() {
+            lints.push(Lint {
+                severity: LintSeverity::Warning,
+                line,
+                message: format!("Link to missing note: {}", link),
+            });
+        }
+    }
+
+    // Recurse into contents
+    for child in node.value().contents.lock().unwrap().iter() {
+        lint_links_in_ast(child, root, lints);
+    }
+    // Recurse into children
+    for child in node.value().children.lock().unwrap().iter() {
+        lint_links_in_ast(child, root, lints);
+    }
+}
+
+/// Append one note to another, repoint wikilinks at the merged note and trash the source
+/// Returns the number of links rewritten across the workspace
+#[tauri::command]
+pub fn merge_notes(
+    app: AppHandle,
+    root: PathBuf,
+    source_path: String,
+    target_path: String,
+    separator: Option<String>,
+) -> Result<usize, String> {
+    if source_path == target_path {
+        return Err("Cannot merge a note into itself".to_string());
+    }
+
+    let source_full = resolve_within_root(&root, &source_path)?;
+    let target_full = resolve_within_root(&root, &target_path)?;
+
+    if !source_full.exists() {
+        return Err(format!("File not found: {}", source_path));
+    }
+    if !target_full.exists() {
+        return Err(format!("File not found: {}", target_path));
+    }
+
+    let source_content =
+        fs::read_to_string(&source_full).map_err(|e| format!("Failed to read file: {}", e))?;
+    let mut merged =
+        fs::read_to_string(&target_full).map_err(|e| format!("Failed to read file: {}", e))?;
+
+    if !merged.is_empty() && !merged.ends_with('\n') {
+        merged.push('\n');
+    }
+    if let Some(separator) = separator {
+        merged.push_str(&separator);
+        merged.push('\n');
+    }
+    merged.push_str(&source_content);
+
+    // Trash the source first so a failure can't leave its content in both notes,
+    // and so the scan below doesn't include it
+    let trashed = move_to_trash(&root, &source_path)?;
+    invalidate_note_caches(&app, &source_full);
+
+    if let Err(e) = fs::write(&target_full, merged) {
+        let _ = fs::rename(&trashed, &source_full);
+        return Err(write_error_message(&e, &target_path));
+    }
+    invalidate_note_caches(&app, &target_full);
+
+    let from = source_path.trim_end_matches(".pn");
+    let to = target_path.trim_end_matches(".pn");
+    let mut rewritten = 0;
+
+    for file_path in collect_patto_files(&root).map_err(|e| e.to_string())? {
+        let full_path = root.join(&file_path);
+        let Ok(content) = fs::read_to_string(&full_path) else {
+            continue;
+        };
+
+        let (updated, count) = rewrite_wikilinks(&content, from, to);
+        if count > 0 {
+            fs::write(&full_path, updated).map_err(|e| write_error_message(&e, &file_path))?;
+            invalidate_note_caches(&app, &full_path);
+            emit_tasks_changed(&app, &file_path);
+            rewritten += count;
+        }
+    }
+
+    emit_tasks_changed(&app, &source_path);
+    emit_tasks_changed(&app, &target_path);
+
+    Ok(rewritten)
+}
+
+/// Replace `[from]` and `[from#anchor]` wikilinks with links to `to`
+/// Only spans the parser reads as wikilinks are touched, so code and plain brackets stay as is
+fn rewrite_wikilinks(content: &str, from: &str, to: &str) -> (String, usize) {
+    let mut spans = Vec::new();
+    collect_wikilink_spans(&parser::parse_text(content).ast, from, &mut spans);
+    if spans.is_empty() {
+        return (content.to_string(), 0);
+    }
+
+    let pattern = format!("[{}", from);
+    let mut count = 0;
+    let mut lines: Vec<String> = content.split_inclusive('\n').map(String::from).collect();
+
+    // Right to left, so earlier spans on a line keep their offsets
+    spans.sort_unstable_by(|a, b| b.cmp(a));
+    for (row, start, end) in spans {
+        let Some(line) = lines.get_mut(row) else {
+            continue;
+        };
+        if line
+            .get(start..end)
+            .is_some_and(|link| link.starts_with(&pattern))
+        {
+            line.replace_range(start..start + pattern.len(), &format!("[{}", to));
+            count += 1;
+        }
+    }
+
+    (lines.concat(), count)
+}
+
+/// Line, start and end byte offsets of the wikilinks to `target`
+fn collect_wikilink_spans(
+    node: &parser::AstNode,
+    target: &str,
+    spans: &mut Vec<(usize, usize, usize)>,
+) {
+    if let parser::AstNodeKind::WikiLink { link, .. } = node.kind() {
+        if link == target {
+            let location = node.location();
+            spans.push((location.row, location.span.0, location.span.1));
+        }
+    }
+
+    for child in node.value().contents.lock().unwrap().iter() {
+        collect_wikilink_spans(child, target, spans);
+    }
+    for child in node.value().children.lock().unwrap().iter() {
+        collect_wikilink_spans(child, target, spans);
+    }
+}
+
+/// Path of the note a wikilink target refers to
+pub(crate) fn note_path(root: &Path, link: &str) -> PathBuf {
+    root.join(format!("{}.pn", link))
+}
+
+/// Anchor defined in a note
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnchorInfo {
+    pub name: String,
+    pub line_number: usize,
+}
+
+/// List all anchors defined in a note (for in-note navigation)
+#[tauri::command]
+pub fn get_anchors(root: PathBuf, file_path: String) -> Result<Vec<AnchorInfo>, String> {
+    let full_path = resolve_within_root(&root, &file_path)?;
+
+    if !full_path.exists() {
+        return Err(format!("File not found: {}", file_path));
+    }
+
+    let content =
+        fs::read_to_string(&full_path).map_err(|e| format!("Failed to read file: {}", e))?;
+
+    // Parse the content
+    let parse_result = parser::parse_text(&content);
+
+    let mut anchors = Vec::new();
+    extract_anchors_from_ast(&parse_result.ast, &mut anchors);
+
+    Ok(anchors)
+}
+
+fn extract_anchors_from_ast(node: &parser::AstNode, anchors: &mut Vec<AnchorInfo>) {
+    use parser::{AstNodeKind, Property};
+
+    // Properties are inside Line and QuoteContent kinds
+    if let AstNodeKind::Line { properties } | AstNodeKind::QuoteContent { properties } = node.kind()
+    {
+        for prop in properties {
+            if let Property::Anchor { name, location } = prop {
+                anchors.push(AnchorInfo {
+                    name: name.clone(),
+                    line_number: location.row,
+                });
+            }
+        }
+    }
+
+    // Recurse into contents
+    for child in node.value().contents.lock().unwrap().iter() {
+        extract_anchors_from_ast(child, anchors);
+    }
+    // Recurse into children
+    for child in node.value().children.lock().unwrap().iter() {
+        extract_anchors_from_ast(child, anchors);
+    }
+}
+
 /// Get image as base64 data URL
 #[tauri::command]
 pub fn get_image_base64(path: String) -> Result<String, String> {
+    image_data_url(Path::new(&path))
+}
+
+/// Largest thumbnail edge `get_thumbnail` renders, in pixels
+const MAX_THUMBNAIL_SIZE: u32 = 1024;
+
+/// Get a square thumbnail of an image as base64 data URL
+/// Thumbnails are cached in the app cache dir keyed by path + mtime + size
+#[tauri::command]
+pub fn get_thumbnail(app: AppHandle, path: String, size: u32) -> Result<String, String> {
     use base64::{engine::general_purpose::STANDARD, Engine};
+    use image::imageops::FilterType;
+    use image::{DynamicImage, ImageFormat};
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
 
-    let path = std::path::Path::new(&path);
-    if !path.exists() {
-        return Err(format!("Image not found: {:?}", path));
+    let source = Path::new(&path);
+    if !source.exists() {
+        return Err(format!("Image not found: {:?}", source));
     }
 
-    let data = fs::read(path).map_err(|e| format!("Failed to read image: {}", e))?;
+    if size == 0 {
+        return Err("Thumbnail size must be greater than 0".to_string());
+    }
+    // `resize_to_fill` upscales, so an unbounded size could allocate gigabytes
+    let size = size.min(MAX_THUMBNAIL_SIZE);
+
+    // SVG is already resolution independent, return as-is
+    let mime = image_mime_type(source);
+    if mime == "image/svg+xml" {
+        return get_image_base64(path);
+    }
+
+    let modified_time = fs::metadata(source)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
 
-    // Determine MIME type from extension
-    let mime = match path.extension().and_then(|e| e.to_str()) {
-        Some("png") => "image/png",
-        Some("jpg") | Some("jpeg") => "image/jpeg",
-        Some("gif") => "image/gif",
-        Some("webp") => "image/webp",
-        Some("svg") => "image/svg+xml",
-        _ => "application/octet-stream",
+    // Keep JPEG as JPEG (smaller), everything else becomes PNG
+    let (format, extension) = if mime == "image/jpeg" {
+        (ImageFormat::Jpeg, "jpg")
+    } else {
+        (ImageFormat::Png, "png")
     };
 
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    modified_time.hash(&mut hasher);
+    size.hash(&mut hasher);
+
+    let cache_dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| format!("Failed to get cache dir: {}", e))?
+        .join("thumbnails");
+    let cache_path = cache_dir.join(format!("{:016x}.{}", hasher.finish(), extension));
+
+    if !cache_path.exists() {
+        fs::create_dir_all(&cache_dir).map_err(|e| format!("Failed to create cache dir: {}", e))?;
+
+        let img = image::open(source).map_err(|e| format!("Failed to decode image: {}", e))?;
+        let img = match exif_orientation(source) {
+            Some(orientation) => apply_orientation(img, orientation),
+            None => img,
+        };
+
+        // Crop to fill a size x size square
+        let thumbnail = img.resize_to_fill(size, size, FilterType::Triangle);
+
+        // JPEG has no alpha channel
+        let thumbnail = match format {
+            ImageFormat::Jpeg => DynamicImage::ImageRgb8(thumbnail.to_rgb8()),
+            _ => thumbnail,
+        };
+
+        // Write to a temp file first so a concurrent call or a crash never leaves
+        // a truncated thumbnail that would be served from then on
+        let temp_path = cache_path.with_extension(format!("{:016x}.tmp", rand::random::<u64>()));
+        thumbnail
+            .save_with_format(&temp_path, format)
+            .and_then(|_| fs::rename(&temp_path, &cache_path).map_err(Into::into))
+            .map_err(|e| {
+                let _ = fs::remove_file(&temp_path);
+                format!("Failed to write thumbnail: {}", e)
+            })?;
+    }
+
+    let data = fs::read(&cache_path).map_err(|e| format!("Failed to read thumbnail: {}", e))?;
+
     let base64 = STANDARD.encode(&data);
-    Ok(format!("data:{};base64,{}", mime, base64))
+    Ok(format!(
+        "data:{};base64,{}",
+        image_mime_type(&cache_path),
+        base64
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::tasks::collect_all_tasks_with_progress;
+    use std::sync::Arc;
+
+    #[test]
+    fn rewrite_wikilinks_only_touches_wikilinks() {
+        let content = "see [old] and [old#intro]\n\
+                       not [older]\n\
+                       [@code]\n\
+                       \t[old]\n";
+        let (updated, count) = rewrite_wikilinks(content, "old", "new");
+
+        assert_eq!(count, 2);
+        assert!(updated.starts_with("see [new] and [new#intro]\n"));
+        assert!(updated.contains("[older]"));
+        assert!(updated.ends_with("\t[old]\n"));
+    }
+
+    #[test]
+    fn caches_stay_fresh_under_concurrent_writes() {
+        let workspace = tempfile::tempdir().unwrap();
+        let root = workspace.path().to_path_buf();
+        let links = Arc::new(LinkCache::default());
+        let tasks = Arc::new(TaskCache::default());
+        for writer in 0..4 {
+            fs::write(root.join(format!("target-{}.pn", writer)), "target\n").unwrap();
+        }
+
+        let handles: Vec<_> = (0..4)
+            .map(|writer| {
+                let (root, links, tasks) = (root.clone(), Arc::clone(&links), Arc::clone(&tasks));
+                std::thread::spawn(move || {
+                    let note = format!("writer-{}.pn", writer);
+                    let target = format!("target-{}.pn", writer);
+                    let full_path = root.join(&note);
+
+                    for round in 0..40 {
+                        // Each writer owns one note, so its counts are known after every write
+                        let open = round % 5;
+                        let linked = round % 2 == 0;
+                        let mut content: String = (0..open)
+                            .map(|n| format!("task {} {{@task status=todo}}\n", n))
+                            .collect();
+                        if linked {
+                            content.push_str(&format!("[target-{}]\n", writer));
+                        }
+
+                        // Same steps as `write_note`
+                        fs::write(&full_path, content).unwrap();
+                        invalidate_cached_note(&links, &tasks, &full_path);
+
+                        // Same scans as `get_all_tasks` and `list_files` with backlinks
+                        let all = collect_all_tasks_with_progress(&root, None, Some(&tasks), None)
+                            .unwrap();
+                        assert_eq!(all.iter().filter(|t| t.file_path == note).count(), open);
+
+                        let counts = links.backlink_counts(&root).unwrap();
+                        assert_eq!(counts.get(&target).copied().unwrap_or(0), linked as u32);
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
 }
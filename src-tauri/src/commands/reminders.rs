@@ -0,0 +1,113 @@
+// Task reminders for patto-mobile
+// Schedules local notifications ahead of task deadlines
+
+use crate::commands::tasks::{collect_all_tasks, TaskItem};
+use chrono::{Local, TimeZone};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, State};
+use tauri_plugin_notification::{NotificationExt, Schedule};
+
+/// Notification ids of the currently scheduled reminders
+#[derive(Default)]
+pub struct ReminderState {
+    scheduled: Mutex<Vec<i32>>,
+}
+
+/// Schedule a local notification `lead_minutes` before each upcoming open task
+/// Returns how many reminders were scheduled
+#[tauri::command]
+pub async fn schedule_task_reminders(
+    app: AppHandle,
+    state: State<'_, ReminderState>,
+    root: PathBuf,
+    lead_minutes: u32,
+) -> Result<usize, String> {
+    let tasks = tokio::task::spawn_blocking(move || collect_all_tasks(&root))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))??;
+
+    // Replace any previously scheduled reminders
+    cancel_scheduled(&app, &state)?;
+
+    let now = Local::now().timestamp();
+    let mut seen = HashSet::new();
+    let mut ids = Vec::new();
+
+    for task in tasks.iter().filter(|t| t.status != "done") {
+        let Some(trigger) = reminder_time(task, lead_minutes) else {
+            continue;
+        };
+        if trigger <= now {
+            continue;
+        }
+
+        // One reminder per task line
+        let id = reminder_id(task);
+        if !seen.insert(id) {
+            continue;
+        }
+
+        let date = time::OffsetDateTime::from_unix_timestamp(trigger)
+            .map_err(|e| format!("Invalid reminder time: {}", e))?;
+
+        app.notification()
+            .builder()
+            .id(id)
+            .title(task.file_name.clone())
+            .body(match &task.due_date {
+                Some(due) => format!("{} (due {})", task.content.trim(), due),
+                None => task.content.trim().to_string(),
+            })
+            .schedule(Schedule::At {
+                date,
+                repeating: false,
+                allow_while_idle: true,
+            })
+            .show()
+            .map_err(|e| format!("Failed to schedule reminder: {}", e))?;
+
+        ids.push(id);
+    }
+
+    let count = ids.len();
+    *state.scheduled.lock().unwrap() = ids;
+
+    Ok(count)
+}
+
+/// Cancel all reminders scheduled by `schedule_task_reminders`
+#[tauri::command]
+pub fn clear_task_reminders(app: AppHandle, state: State<'_, ReminderState>) -> Result<(), String> {
+    cancel_scheduled(&app, &state)
+}
+
+fn cancel_scheduled(app: &AppHandle, state: &ReminderState) -> Result<(), String> {
+    let ids: Vec<i32> = state.scheduled.lock().unwrap().drain(..).collect();
+    if ids.is_empty() {
+        return Ok(());
+    }
+
+    app.notification()
+        .cancel(ids)
+        .map_err(|e| format!("Failed to cancel reminders: {}", e))
+}
+
+/// Unix time at which to remind about a task
+/// Deadlines are local wall-clock times stored as if they were UTC
+fn reminder_time(task: &TaskItem, lead_minutes: u32) -> Option<i64> {
+    let due = chrono::DateTime::from_timestamp(task.due_timestamp?, 0)?.naive_utc();
+    let due = Local.from_local_datetime(&due).earliest()?;
+    Some(due.timestamp() - lead_minutes as i64 * 60)
+}
+
+/// Stable notification id for a task, derived from file and line
+fn reminder_id(task: &TaskItem) -> i32 {
+    let mut hasher = DefaultHasher::new();
+    task.file_path.hash(&mut hasher);
+    task.line_number.hash(&mut hasher);
+    (hasher.finish() & 0x7fff_ffff) as i32
+}
@@ -0,0 +1,158 @@
+// Full-text search for patto-mobile
+// Streams notes line by line so large workspaces don't have to fit in memory
+
+use crate::commands::tasks::collect_patto_files;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+/// Hits returned by `search_notes` when no limit is given
+const DEFAULT_SEARCH_LIMIT: usize = 200;
+
+/// Lines of context kept before and after a matching line
+const CONTEXT_LINES: usize = 1;
+
+/// Line matching a search query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub file_path: String,
+    /// 0-based, like the line numbers of tasks and links
+    pub line_number: usize,
+    /// Matching line with up to `CONTEXT_LINES` lines of context on each side
+    pub snippet: String,
+}
+
+/// Search every note for `query`
+/// Stops after `limit` hits (default 200).
+#[tauri::command]
+pub async fn search_notes(
+    root: PathBuf,
+    query: String,
+    case_sensitive: bool,
+    limit: Option<usize>,
+) -> Result<Vec<SearchHit>, String> {
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    tokio::task::spawn_blocking(move || {
+        let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+        let needle = if case_sensitive {
+            query
+        } else {
+            query.to_lowercase()
+        };
+
+        let mut files = collect_patto_files(&root).map_err(|e| e.to_string())?;
+        files.sort();
+
+        let mut hits = Vec::new();
+        for file_path in files {
+            if hits.len() >= limit {
+                break;
+            }
+            // Unreadable files are skipped rather than failing the search
+            let _ = search_file(
+                &root.join(&file_path),
+                &file_path,
+                &needle,
+                case_sensitive,
+                limit,
+                &mut hits,
+            );
+        }
+
+        Ok(hits)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Append hits from one file until `hits` holds `limit` entries
+fn search_file(
+    full_path: &Path,
+    file_path: &str,
+    needle: &str,
+    case_sensitive: bool,
+    limit: usize,
+    hits: &mut Vec<SearchHit>,
+) -> std::io::Result<()> {
+    let mut reader = BufReader::new(fs::File::open(full_path)?);
+    let mut before: VecDeque<String> = VecDeque::with_capacity(CONTEXT_LINES);
+    // Hits still waiting for their trailing context: (index in `hits`, lines still needed)
+    let mut pending: Vec<(usize, usize)> = Vec::new();
+    let mut buf = Vec::new();
+    let mut next_line = 0;
+
+    loop {
+        buf.clear();
+        if reader.read_until(b'\n', &mut buf)? == 0 {
+            break;
+        }
+        let line_number = next_line;
+        next_line += 1;
+        let line = String::from_utf8_lossy(&buf)
+            .trim_end_matches(['\n', '\r'])
+            .to_string();
+
+        for (index, needed) in pending.iter_mut() {
+            hits[*index].snippet.push('\n');
+            hits[*index].snippet.push_str(&line);
+            *needed -= 1;
+        }
+        pending.retain(|(_, needed)| *needed > 0);
+
+        let matched = if case_sensitive {
+            line.contains(needle)
+        } else {
+            line.to_lowercase().contains(needle)
+        };
+
+        if matched && hits.len() < limit {
+            let mut snippet = before.iter().cloned().collect::<Vec<_>>().join("\n");
+            if !snippet.is_empty() {
+                snippet.push('\n');
+            }
+            snippet.push_str(&line);
+
+            hits.push(SearchHit {
+                file_path: file_path.to_string(),
+                line_number,
+                snippet,
+            });
+            pending.push((hits.len() - 1, CONTEXT_LINES));
+        }
+
+        if hits.len() >= limit && pending.is_empty() {
+            break;
+        }
+
+        if before.len() == CONTEXT_LINES {
+            before.pop_front();
+        }
+        before.push_back(line);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_file_reports_zero_based_line_numbers() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("note.pn");
+        fs::write(&path, "needle\nhay\nneedle\n").unwrap();
+
+        let mut hits = Vec::new();
+        search_file(&path, "note.pn", "needle", true, 10, &mut hits).unwrap();
+
+        let lines: Vec<usize> = hits.iter().map(|h| h.line_number).collect();
+        assert_eq!(lines, [0, 2]);
+    }
+}
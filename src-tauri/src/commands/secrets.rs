@@ -0,0 +1,168 @@
+// Secret scanning for patto-mobile
+// Catches API keys and tokens pasted into notes before they get pushed
+
+use crate::commands::tasks::collect_patto_files;
+use git2::Repository;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Minimum length of a token checked by the generic entropy rule
+const MIN_TOKEN_LENGTH: usize = 32;
+
+/// Shannon entropy (bits per char) above which a token looks random
+const ENTROPY_THRESHOLD: f64 = 4.0;
+
+/// Possible secret found in a note
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretHit {
+    pub file_path: String,
+    pub line_number: usize,
+    pub rule: String,
+    /// Match with all but the first few characters masked
+    pub redacted_match: String,
+}
+
+/// Scan notes for likely secrets
+/// Without `paths`, scans files changed in git (or every note outside a repository)
+#[tauri::command]
+pub async fn scan_for_secrets(
+    root: PathBuf,
+    paths: Option<Vec<String>>,
+) -> Result<Vec<SecretHit>, String> {
+    tokio::task::spawn_blocking(move || {
+        let paths = match paths {
+            Some(paths) => paths,
+            None => match Repository::open(&root) {
+                Ok(repo) => changed_paths(&repo)?,
+                Err(_) => collect_patto_files(&root).map_err(|e| e.to_string())?,
+            },
+        };
+
+        Ok(scan_files(&root, &paths))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Scan the given files (relative to `root`), skipping unreadable or binary ones
+pub(crate) fn scan_files(root: &Path, paths: &[String]) -> Vec<SecretHit> {
+    let mut hits = Vec::new();
+
+    for file_path in paths {
+        let Ok(content) = fs::read_to_string(root.join(file_path)) else {
+            continue;
+        };
+
+        for (i, line) in content.lines().enumerate() {
+            for (rule, matched) in scan_line(line) {
+                hits.push(SecretHit {
+                    file_path: file_path.clone(),
+                    line_number: i,
+                    rule: rule.to_string(),
+                    redacted_match: redact(matched),
+                });
+            }
+        }
+    }
+
+    hits
+}
+
+/// Modified and untracked files in the working tree or index
+fn changed_paths(repo: &Repository) -> Result<Vec<String>, String> {
+    let mut options = git2::StatusOptions::new();
+    options.include_untracked(true).recurse_untracked_dirs(true);
+
+    let statuses = repo
+        .statuses(Some(&mut options))
+        .map_err(|e| format!("Failed to get status: {}", e))?;
+
+    Ok(statuses
+        .iter()
+        .filter(|entry| {
+            let status = entry.status();
+            status.is_wt_new()
+                || status.is_wt_modified()
+                || status.is_index_new()
+                || status.is_index_modified()
+        })
+        .filter_map(|entry| entry.path().map(|p| p.to_string()))
+        .collect())
+}
+
+fn rules() -> &'static [(&'static str, Regex)] {
+    static RULES: OnceLock<Vec<(&'static str, Regex)>> = OnceLock::new();
+    RULES.get_or_init(|| {
+        [
+            ("aws-access-key", r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b"),
+            ("github-token", r"\bgh[pousr]_[A-Za-z0-9]{36,}\b"),
+            ("github-pat", r"\bgithub_pat_[A-Za-z0-9_]{22,}\b"),
+            ("gitlab-pat", r"\bglpat-[A-Za-z0-9_-]{20,}\b"),
+            ("slack-token", r"\bxox[abprs]-[A-Za-z0-9-]{10,}\b"),
+            ("private-key", r"-----BEGIN [A-Z ]*PRIVATE KEY-----"),
+        ]
+        .into_iter()
+        .map(|(rule, pattern)| (rule, Regex::new(pattern).unwrap()))
+        .collect()
+    })
+}
+
+fn token_regex() -> &'static Regex {
+    static TOKEN: OnceLock<Regex> = OnceLock::new();
+    TOKEN.get_or_init(|| Regex::new(r"[A-Za-z0-9+/_=-]{32,}").unwrap())
+}
+
+/// Matches on a single line as (rule, matched text)
+fn scan_line(line: &str) -> Vec<(&'static str, &str)> {
+    let mut found: Vec<(&'static str, &str)> = Vec::new();
+
+    for (rule, regex) in rules() {
+        for m in regex.find_iter(line) {
+            found.push((*rule, m.as_str()));
+        }
+    }
+
+    // Generic rule for anything long and random that the specific rules missed
+    for m in token_regex().find_iter(line) {
+        let token = m.as_str();
+        let already_found = found
+            .iter()
+            .any(|(_, f)| token.contains(f) || f.contains(token));
+        if !already_found
+            && token.len() >= MIN_TOKEN_LENGTH
+            && shannon_entropy(token) > ENTROPY_THRESHOLD
+        {
+            found.push(("high-entropy-token", token));
+        }
+    }
+
+    found
+}
+
+fn shannon_entropy(s: &str) -> f64 {
+    let mut counts = [0usize; 256];
+    for b in s.bytes() {
+        counts[b as usize] += 1;
+    }
+
+    let len = s.len() as f64;
+    counts
+        .iter()
+        .filter(|&&c| c > 0)
+        .map(|&c| {
+            let p = c as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Keep the first 4 characters so the user can recognise the secret
+fn redact(s: &str) -> String {
+    let visible: String = s.chars().take(4).collect();
+    let hidden = s.chars().count().saturating_sub(4);
+    format!("{}{}", visible, "*".repeat(hidden))
+}
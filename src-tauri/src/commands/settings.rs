@@ -0,0 +1,132 @@
+// App settings for patto-mobile
+// Persists the active workspace path and git identity to the app config dir so they survive restarts
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State};
+
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Settings stored as JSON in the app config dir
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub workspace_path: Option<PathBuf>,
+    pub git_identity: Option<GitIdentity>,
+}
+
+/// Author name and email used for commits made from the app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitIdentity {
+    pub name: String,
+    pub email: String,
+}
+
+/// In-memory copy of the settings, loaded from disk on first access
+#[derive(Default)]
+pub struct SettingsState {
+    settings: Mutex<Option<Settings>>,
+}
+
+/// Get the active workspace path, if one has been set
+#[tauri::command]
+pub fn get_workspace_path(
+    app: AppHandle,
+    state: State<'_, SettingsState>,
+) -> Result<Option<PathBuf>, String> {
+    let mut settings = state.settings.lock().unwrap();
+    let settings = settings.get_or_insert_with(|| load_settings(&app));
+
+    Ok(settings.workspace_path.clone())
+}
+
+/// Set and persist the active workspace path
+/// The path must be an existing directory
+#[tauri::command]
+pub fn set_workspace_path(
+    app: AppHandle,
+    state: State<'_, SettingsState>,
+    path: PathBuf,
+) -> Result<(), String> {
+    if !path.is_dir() {
+        return Err(format!("Not a directory: {}", path.display()));
+    }
+
+    let mut settings = state.settings.lock().unwrap();
+    let settings = settings.get_or_insert_with(|| load_settings(&app));
+    settings.workspace_path = Some(path);
+
+    save_settings(&app, settings)
+}
+
+/// Get the commit author identity, if one has been set
+#[tauri::command]
+pub fn get_git_identity(
+    app: AppHandle,
+    state: State<'_, SettingsState>,
+) -> Result<Option<GitIdentity>, String> {
+    Ok(git_identity(&app, &state))
+}
+
+/// Set and persist the commit author identity
+/// The name must be non-empty and the email must contain an `@`
+#[tauri::command]
+pub fn set_git_identity(
+    app: AppHandle,
+    state: State<'_, SettingsState>,
+    name: String,
+    email: String,
+) -> Result<(), String> {
+    let name = name.trim().to_string();
+    let email = email.trim().to_string();
+    if name.is_empty() {
+        return Err("Name must not be empty".to_string());
+    }
+    if !email.contains('@') {
+        return Err(format!("Invalid email address: {}", email));
+    }
+
+    let mut settings = state.settings.lock().unwrap();
+    let settings = settings.get_or_insert_with(|| load_settings(&app));
+    settings.git_identity = Some(GitIdentity { name, email });
+
+    save_settings(&app, settings)
+}
+
+/// Commit author identity from the settings, for use by the git commands
+pub fn git_identity(app: &AppHandle, state: &SettingsState) -> Option<GitIdentity> {
+    let mut settings = state.settings.lock().unwrap();
+    let settings = settings.get_or_insert_with(|| load_settings(app));
+
+    settings.git_identity.clone()
+}
+
+fn settings_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(SETTINGS_FILE))
+        .map_err(|e| format!("Failed to get config dir: {}", e))
+}
+
+/// Missing or unreadable settings fall back to defaults
+fn load_settings(app: &AppHandle) -> Settings {
+    settings_path(app)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_settings(app: &AppHandle, settings: &Settings) -> Result<(), String> {
+    let path = settings_path(app)?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write settings: {}", e))
+}
@@ -1,13 +1,23 @@
 // Task aggregation for patto-mobile
 // Gathers tasks from all notes and categorizes by deadline
 
+use crate::commands::files::resolve_within_root;
+use crate::commands::notes::check_note_size;
 use chrono::{Local, NaiveDate};
+use git2::Repository;
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use patto::parser::{
     self, AstNode, AstNodeKind, Deadline, Property, TaskStatus as PattoTaskStatus,
 };
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::sync::RwLock;
+use std::time::SystemTime;
+use tauri::{AppHandle, Emitter, Manager};
 
 /// Single task item
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,9 +27,33 @@ pub struct TaskItem {
     pub file_name: String,
     pub line_number: usize,
     pub content: String,
+    pub full_content: String,
     pub status: String,
     pub due_date: Option<String>,
     pub due_timestamp: Option<i64>,
+    pub scheduled_date: Option<String>,
+    pub scheduled_timestamp: Option<i64>,
+    pub recurrence_days: Option<u32>,
+    pub priority: u8,
+    pub completed_date: Option<String>,
+    pub completed_timestamp: Option<i64>,
+}
+
+/// Event payload emitted when a command changes the tasks of a note
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TasksChanged {
+    pub file_path: String,
+}
+
+/// Notify open views that tasks in a note may have changed
+pub fn emit_tasks_changed(app: &AppHandle, file_path: &str) {
+    let _ = app.emit(
+        "tasks-changed",
+        TasksChanged {
+            file_path: file_path.to_string(),
+        },
+    );
 }
 
 /// Categorized tasks by deadline
@@ -31,87 +65,703 @@ pub struct TaskAggregation {
     pub this_week: Vec<TaskItem>,
     pub later: Vec<TaskItem>,
     pub no_deadline: Vec<TaskItem>,
+    pub scheduled: Vec<TaskItem>,
     pub done: Vec<TaskItem>,
 }
 
+/// Progress event for the task scan
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskScanProgress {
+    pub processed: usize,
+    pub total: usize,
+    pub percent: u32,
+}
+
 /// Get all tasks from workspace categorized by deadline
+/// With `report_progress`, emits `task-scan-progress` events during the scan.
+/// With `respect_gitignore`, notes ignored by the workspace repository's `.gitignore` are skipped.
+#[tauri::command]
+pub async fn get_all_tasks(
+    app: AppHandle,
+    root: PathBuf,
+    report_progress: Option<bool>,
+    respect_gitignore: Option<bool>,
+) -> Result<TaskAggregation, String> {
+    tokio::task::spawn_blocking(move || {
+        let progress = report_progress.unwrap_or(false).then_some(&app);
+        let cache = app.state::<TaskCache>();
+        let gitignore = gitignore_repo(&root, respect_gitignore.unwrap_or(false));
+        aggregate_tasks(&root, progress, Some(cache.inner()), gitignore.as_ref())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Categorize all workspace tasks by deadline
+fn aggregate_tasks(
+    root: &Path,
+    progress: Option<&AppHandle>,
+    cache: Option<&TaskCache>,
+    gitignore: Option<&Repository>,
+) -> Result<TaskAggregation, String> {
+    let mut aggregation = TaskAggregation::default();
+    let today = Local::now().date_naive();
+    let week_end = today + chrono::Duration::days(7);
+
+    for task in collect_all_tasks_with_progress(root, progress, cache, gitignore)? {
+        match task.status.as_str() {
+            "done" => {
+                aggregation.done.push(task);
+            }
+            // Hide tasks until their start date
+            _ if task_scheduled_date(&task).is_some_and(|d| d > today) => {
+                aggregation.scheduled.push(task);
+            }
+            _ => {
+                // Categorize by deadline
+                match task_due_date(&task) {
+                    Some(due_date) => {
+                        if due_date < today {
+                            aggregation.overdue.push(task);
+                        } else if due_date == today {
+                            aggregation.today.push(task);
+                        } else if due_date <= week_end {
+                            aggregation.this_week.push(task);
+                        } else {
+                            aggregation.later.push(task);
+                        }
+                    }
+                    None => {
+                        aggregation.no_deadline.push(task);
+                    }
+                }
+            }
+        }
+    }
+
+    // Sort each category by priority, then due date
+    sort_by_priority(&mut aggregation.overdue);
+    sort_by_priority(&mut aggregation.today);
+    sort_by_priority(&mut aggregation.this_week);
+    sort_by_priority(&mut aggregation.later);
+    sort_by_priority(&mut aggregation.no_deadline);
+    aggregation
+        .scheduled
+        .sort_by(|a, b| a.scheduled_timestamp.cmp(&b.scheduled_timestamp));
+
+    Ok(aggregation)
+}
+
+/// Get tasks for a calendar month, keyed by due date (YYYY-MM-DD)
+#[tauri::command]
+pub async fn get_task_calendar(
+    root: PathBuf,
+    year: i32,
+    month: u32,
+) -> Result<HashMap<String, Vec<TaskItem>>, String> {
+    tokio::task::spawn_blocking(move || {
+        let month_start = NaiveDate::from_ymd_opt(year, month, 1)
+            .ok_or_else(|| format!("Invalid month: {}-{}", year, month))?;
+        let next_month_start = if month == 12 {
+            NaiveDate::from_ymd_opt(year + 1, 1, 1)
+        } else {
+            NaiveDate::from_ymd_opt(year, month + 1, 1)
+        }
+        .ok_or_else(|| format!("Invalid month: {}-{}", year, month))?;
+
+        let mut calendar: HashMap<String, Vec<TaskItem>> = HashMap::new();
+
+        for task in collect_all_tasks(&root)? {
+            if let Some(due_date) = task_due_date(&task) {
+                if due_date >= month_start && due_date < next_month_start {
+                    calendar
+                        .entry(due_date.format("%Y-%m-%d").to_string())
+                        .or_default()
+                        .push(task);
+                }
+            }
+        }
+
+        for tasks in calendar.values_mut() {
+            tasks.sort_by(|a, b| a.due_timestamp.cmp(&b.due_timestamp));
+        }
+
+        Ok(calendar)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Sort tasks by priority (highest first), then by due date
+/// Unprioritized tasks (priority 0) sort last
+fn sort_by_priority(tasks: &mut [TaskItem]) {
+    tasks.sort_by(|a, b| {
+        b.priority
+            .cmp(&a.priority)
+            .then_with(|| a.due_timestamp.cmp(&b.due_timestamp))
+    });
+}
+
+/// Get done tasks completed within a date range (inclusive, YYYY-MM-DD)
+/// sorted by completion date
+#[tauri::command]
+pub async fn get_completed_tasks(
+    root: PathBuf,
+    since: String,
+    until: String,
+) -> Result<Vec<TaskItem>, String> {
+    tokio::task::spawn_blocking(move || {
+        let since = NaiveDate::parse_from_str(&since, "%Y-%m-%d")
+            .map_err(|e| format!("Invalid since date: {}", e))?;
+        let until = NaiveDate::parse_from_str(&until, "%Y-%m-%d")
+            .map_err(|e| format!("Invalid until date: {}", e))?;
+
+        let mut tasks: Vec<TaskItem> = collect_all_tasks(&root)?
+            .into_iter()
+            .filter(|task| {
+                task.completed_timestamp
+                    .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+                    .map(|dt| dt.naive_utc().date())
+                    .is_some_and(|d| d >= since && d <= until)
+            })
+            .collect();
+
+        tasks.sort_by(|a, b| a.completed_timestamp.cmp(&b.completed_timestamp));
+
+        Ok(tasks)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Get open tasks due between now and `within_hours` from now, soonest first
 #[tauri::command]
-pub async fn get_all_tasks(root: PathBuf) -> Result<TaskAggregation, String> {
+pub async fn get_due_soon(root: PathBuf, within_hours: u32) -> Result<Vec<TaskItem>, String> {
+    tokio::task::spawn_blocking(move || {
+        // Deadlines are local wall-clock times stored as if they were UTC
+        let now = Local::now().naive_local().and_utc().timestamp();
+        let until = now + within_hours as i64 * 3600;
+
+        let mut tasks: Vec<TaskItem> = collect_all_tasks(&root)?
+            .into_iter()
+            .filter(|task| task.status != "done")
+            .filter(|task| {
+                task.due_timestamp
+                    .is_some_and(|ts| ts >= now && ts <= until)
+            })
+            .collect();
+
+        tasks.sort_by(|a, b| a.due_timestamp.cmp(&b.due_timestamp));
+
+        Ok(tasks)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Number of open tasks due within a date range (inclusive)
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistogramBucket {
+    pub start_date: String,
+    pub end_date: String,
+    pub count: usize,
+}
+
+/// Distribution of open task deadlines from today forward
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadlineHistogram {
+    pub buckets: Vec<HistogramBucket>,
+    pub overdue: usize,
+    pub no_deadline: usize,
+    /// Tasks due after the last bucket
+    pub later: usize,
+}
+
+/// Count open tasks per `bucket_days`-wide bucket, starting today
+#[tauri::command]
+pub async fn get_deadline_histogram(
+    root: PathBuf,
+    bucket_days: u32,
+    buckets: u32,
+) -> Result<DeadlineHistogram, String> {
+    if bucket_days == 0 {
+        return Err("bucket_days must be greater than 0".to_string());
+    }
+
     tokio::task::spawn_blocking(move || {
-        let mut aggregation = TaskAggregation::default();
         let today = Local::now().date_naive();
-        let week_end = today + chrono::Duration::days(7);
+        let bucket_start = |i: u32| today + chrono::Duration::days(i as i64 * bucket_days as i64);
 
-        // Collect all patto files
-        let files = collect_patto_files(&root).map_err(|e| e.to_string())?;
+        let mut histogram = DeadlineHistogram {
+            buckets: (0..buckets)
+                .map(|i| HistogramBucket {
+                    start_date: bucket_start(i).format("%Y-%m-%d").to_string(),
+                    end_date: (bucket_start(i + 1) - chrono::Duration::days(1))
+                        .format("%Y-%m-%d")
+                        .to_string(),
+                    count: 0,
+                })
+                .collect(),
+            overdue: 0,
+            no_deadline: 0,
+            later: 0,
+        };
 
-        for file_path in files {
-            let full_path = root.join(&file_path);
-            if let Ok(content) = fs::read_to_string(&full_path) {
-                let tasks = extract_tasks_from_content(&content, &file_path);
+        for task in collect_all_tasks(&root)? {
+            if task.status == "done" {
+                continue;
+            }
 
-                for task in tasks {
-                    match task.status.as_str() {
-                        "done" => {
-                            aggregation.done.push(task);
-                        }
-                        _ => {
-                            // Categorize by deadline
-                            match &task.due_timestamp {
-                                Some(ts) => {
-                                    let due_date = chrono::DateTime::from_timestamp(*ts, 0)
-                                        .map(|dt| dt.naive_utc().date())
-                                        .unwrap_or(today);
-
-                                    if due_date < today {
-                                        aggregation.overdue.push(task);
-                                    } else if due_date == today {
-                                        aggregation.today.push(task);
-                                    } else if due_date <= week_end {
-                                        aggregation.this_week.push(task);
-                                    } else {
-                                        aggregation.later.push(task);
-                                    }
-                                }
-                                None => {
-                                    aggregation.no_deadline.push(task);
-                                }
-                            }
-                        }
+            match task_due_date(&task) {
+                None => histogram.no_deadline += 1,
+                Some(due) if due < today => histogram.overdue += 1,
+                Some(due) => {
+                    let index = ((due - today).num_days() / bucket_days as i64) as usize;
+                    match histogram.buckets.get_mut(index) {
+                        Some(bucket) => bucket.count += 1,
+                        None => histogram.later += 1,
                     }
                 }
             }
         }
 
-        // Sort each category by due date
-        aggregation
-            .overdue
-            .sort_by(|a, b| a.due_timestamp.cmp(&b.due_timestamp));
-        aggregation
-            .today
-            .sort_by(|a, b| a.due_timestamp.cmp(&b.due_timestamp));
-        aggregation
-            .this_week
-            .sort_by(|a, b| a.due_timestamp.cmp(&b.due_timestamp));
-        aggregation
-            .later
-            .sort_by(|a, b| a.due_timestamp.cmp(&b.due_timestamp));
-
-        Ok(aggregation)
+        Ok(histogram)
     })
     .await
     .map_err(|e| format!("Task join error: {}", e))?
 }
 
-fn collect_patto_files(root: &Path) -> std::io::Result<Vec<String>> {
+/// Tasks belonging to a single note
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileTaskGroup {
+    pub file_path: String,
+    pub file_name: String,
+    pub tasks: Vec<TaskItem>,
+}
+
+/// Get all tasks grouped by source note, ordered by nearest open deadline
+#[tauri::command]
+pub async fn get_tasks_grouped_by_file(root: PathBuf) -> Result<Vec<FileTaskGroup>, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut groups: HashMap<String, FileTaskGroup> = HashMap::new();
+
+        for task in collect_all_tasks(&root)? {
+            groups
+                .entry(task.file_path.clone())
+                .or_insert_with(|| FileTaskGroup {
+                    file_path: task.file_path.clone(),
+                    file_name: task.file_name.clone(),
+                    tasks: Vec::new(),
+                })
+                .tasks
+                .push(task);
+        }
+
+        let mut groups: Vec<FileTaskGroup> = groups.into_values().collect();
+        for group in groups.iter_mut() {
+            group.tasks.sort_by_key(|t| t.line_number);
+        }
+
+        // Files with the nearest open deadline first, files without deadlines last
+        let nearest_deadline = |group: &FileTaskGroup| {
+            group
+                .tasks
+                .iter()
+                .filter(|t| t.status != "done")
+                .filter_map(|t| t.due_timestamp)
+                .min()
+        };
+        groups.sort_by(|a, b| match (nearest_deadline(a), nearest_deadline(b)) {
+            (Some(a_due), Some(b_due)) => a_due.cmp(&b_due),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => a.file_name.to_lowercase().cmp(&b.file_name.to_lowercase()),
+        });
+
+        Ok(groups)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Tasks parsed from a file, valid while its mtime is unchanged
+struct CachedTasks {
+    modified: SystemTime,
+    tasks: Vec<TaskItem>,
+}
+
+/// Per-file task cache shared by the task commands
+/// Commands run on a thread pool, so the lock is only held for lookups and inserts, never while parsing.
+#[derive(Default)]
+pub struct TaskCache {
+    files: RwLock<HashMap<PathBuf, CachedTasks>>,
+}
+
+impl TaskCache {
+    fn get(&self, path: &Path, modified: SystemTime) -> Option<Vec<TaskItem>> {
+        let files = self.files.read().unwrap();
+        files
+            .get(path)
+            .filter(|cached| cached.modified == modified)
+            .map(|cached| cached.tasks.clone())
+    }
+
+    fn insert(&self, path: PathBuf, modified: SystemTime, tasks: Vec<TaskItem>) {
+        let mut files = self.files.write().unwrap();
+        files.insert(path, CachedTasks { modified, tasks });
+    }
+
+    /// Forget the cached tasks of one file
+    /// Mtimes can be too coarse to notice an edit made right after the last scan
+    pub(crate) fn invalidate(&self, full_path: &Path) {
+        self.files.write().unwrap().remove(full_path);
+    }
+
+    /// Drop every cached entry
+    pub(crate) fn clear(&self) {
+        self.files.write().unwrap().clear();
+    }
+}
+
+/// Extract tasks from every patto file in the workspace
+pub(crate) fn collect_all_tasks(root: &Path) -> Result<Vec<TaskItem>, String> {
+    collect_all_tasks_with_progress(root, None, None, None)
+}
+
+/// Extract tasks from every patto file, optionally emitting `task-scan-progress` events
+/// Files unchanged since they were cached are not parsed again, and files ignored by
+/// `gitignore` are skipped
+pub(crate) fn collect_all_tasks_with_progress(
+    root: &Path,
+    progress: Option<&AppHandle>,
+    cache: Option<&TaskCache>,
+    gitignore: Option<&Repository>,
+) -> Result<Vec<TaskItem>, String> {
+    let mut tasks = Vec::new();
+    let today = Local::now().date_naive();
+
+    // Collect all patto files, minus those excluded by `.pattoignore`
+    let ignore = load_task_ignore(root)?;
     let mut files = Vec::new();
-    collect_patto_files_recursive(root, root, &mut files)?;
+    collect_patto_files_recursive(root, root, ignore.as_ref(), gitignore, &mut files)
+        .map_err(|e| e.to_string())?;
+    let total = files.len();
+    let processed = AtomicUsize::new(0);
+    let last_percent = AtomicU32::new(0);
+
+    // The walk above is serial, parsing is spread over the thread pool
+    let per_file: Vec<Vec<TaskItem>> = files
+        .par_iter()
+        .map(|file_path| {
+            // Only emit every 5% to reduce event spam
+            if let Some(app) = progress {
+                let done = processed.fetch_add(1, Ordering::Relaxed) + 1;
+                let percent = (done * 100 / total) as u32;
+                let prev = last_percent.load(Ordering::Relaxed);
+                if (percent >= prev + 5 || percent == 100)
+                    && last_percent
+                        .compare_exchange(prev, percent, Ordering::Relaxed, Ordering::Relaxed)
+                        .is_ok()
+                {
+                    let _ = app.emit(
+                        "task-scan-progress",
+                        TaskScanProgress {
+                            processed: done,
+                            total,
+                            percent,
+                        },
+                    );
+                }
+            }
+
+            file_tasks(root, file_path, cache)
+        })
+        .collect();
+    tasks.extend(per_file.into_iter().flatten());
+
+    // Recurrence depends on today's date, so it's applied after the cache
+    for task in tasks.iter_mut() {
+        roll_forward_recurrence(task, today);
+    }
+
+    Ok(tasks)
+}
+
+/// Open and done task counts of each of `file_paths`
+/// `None` for files that aren't notes or are excluded by `.pattoignore`
+pub(crate) fn task_counts(
+    root: &Path,
+    file_paths: &[String],
+    cache: Option<&TaskCache>,
+) -> Result<Vec<Option<(usize, usize)>>, String> {
+    let ignore = load_task_ignore(root)?;
+
+    Ok(file_paths
+        .par_iter()
+        .map(|file_path| {
+            let relative = Path::new(file_path);
+            if relative.extension().is_none_or(|e| e != "pn")
+                || is_task_ignored(ignore.as_ref(), relative)
+            {
+                return None;
+            }
+
+            let tasks = file_tasks(root, file_path, cache);
+            let done = tasks.iter().filter(|t| t.status == "done").count();
+            Some((tasks.len() - done, done))
+        })
+        .collect())
+}
+
+/// Tasks of one file, from the cache when its mtime is unchanged
+fn file_tasks(root: &Path, file_path: &str, cache: Option<&TaskCache>) -> Vec<TaskItem> {
+    let full_path = root.join(file_path);
+
+    // Skip pathologically large files instead of blocking on the parser
+    if check_note_size(&full_path, file_path, None).is_err() {
+        return Vec::new();
+    }
+
+    let modified = fs::metadata(&full_path).and_then(|m| m.modified()).ok();
+    if let (Some(cache), Some(modified)) = (cache, modified) {
+        if let Some(cached) = cache.get(&full_path, modified) {
+            return cached;
+        }
+    }
+
+    let Ok(content) = fs::read_to_string(&full_path) else {
+        return Vec::new();
+    };
+    let tasks = extract_tasks_from_content(&content, file_path);
+    if let (Some(cache), Some(modified)) = (cache, modified) {
+        cache.insert(full_path, modified, tasks.clone());
+    }
+
+    tasks
+}
+
+/// Move the deadline of an open recurring task to its next occurrence
+/// With a recorded completion, that is one period after the last completion (possibly
+/// still overdue); otherwise the deadline steps forward to the first occurrence on or after today.
+fn roll_forward_recurrence(task: &mut TaskItem, today: NaiveDate) {
+    if task.status == "done" {
+        return;
+    }
+
+    let (Some(period), Some(due_date), Some(ts)) = (
+        task.recurrence_days,
+        task_due_date(task),
+        task.due_timestamp,
+    ) else {
+        return;
+    };
+    if period == 0 {
+        return;
+    }
+    let period = period as i64;
+
+    let next_date = match task_completed_date(task) {
+        Some(completed) => completed + chrono::Duration::days(period),
+        None => {
+            let days_past = (today - due_date).num_days();
+            if days_past <= 0 {
+                return;
+            }
+            // Number of whole periods needed to reach today (rounded up)
+            let periods = (days_past + period - 1) / period;
+            due_date + chrono::Duration::days(periods * period)
+        }
+    };
+
+    // A completion before the current deadline doesn't move it back
+    if next_date <= due_date {
+        return;
+    }
+
+    // Shift by whole days so a time of day on the deadline is kept
+    let next_ts = ts + (next_date - due_date).num_seconds();
+
+    task.due_timestamp = Some(next_ts);
+    task.due_date = chrono::DateTime::from_timestamp(next_ts, 0).map(|dt| {
+        let dt = dt.naive_utc();
+        if task.due_date.as_deref().is_some_and(|d| d.contains(':')) {
+            dt.format("%Y-%m-%d %H:%M").to_string()
+        } else {
+            dt.format("%Y-%m-%d").to_string()
+        }
+    });
+}
+
+/// Parse a priority marker from a task line
+/// `!`/`!!`/`!!!` and `[#C]`/`[#B]`/`[#A]` map to 1-3, no marker is 0
+fn parse_priority(line: &str) -> u8 {
+    line.split_whitespace()
+        .filter_map(|token| match token {
+            "!" | "[#C]" => Some(1),
+            "!!" | "[#B]" => Some(2),
+            "!!!" | "[#A]" => Some(3),
+            _ => None,
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Find an `every N days/weeks` annotation in a task line and return the period in days
+fn parse_recurrence(line: &str) -> Option<u32> {
+    let lower = line.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let pos = words.iter().position(|w| *w == "every")?;
+    let (count, unit) = match words.get(pos + 1)?.parse::<u32>() {
+        Ok(n) => (n, *words.get(pos + 2)?),
+        Err(_) => (1, words[pos + 1]),
+    };
+
+    let unit_days = match unit.trim_end_matches('.') {
+        "day" | "days" => 1,
+        "week" | "weeks" => 7,
+        _ => return None,
+    };
+
+    Some(count * unit_days).filter(|d| *d > 0)
+}
+
+/// Calendar date a task is due on (date part of the deadline)
+fn task_due_date(task: &TaskItem) -> Option<NaiveDate> {
+    task.due_timestamp
+        .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+        .map(|dt| dt.naive_utc().date())
+}
+
+/// Calendar date a task was last completed on
+fn task_completed_date(task: &TaskItem) -> Option<NaiveDate> {
+    task.completed_timestamp
+        .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+        .map(|dt| dt.naive_utc().date())
+}
+
+/// Calendar date a task is scheduled to start on
+fn task_scheduled_date(task: &TaskItem) -> Option<NaiveDate> {
+    task.scheduled_timestamp
+        .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+        .map(|dt| dt.naive_utc().date())
+}
+
+/// Find a `start=` or `scheduled=` date token in a task line
+/// The patto parser does not model start dates, so this scans the raw line text
+fn parse_scheduled_token(line: &str) -> (Option<String>, Option<i64>) {
+    parse_date_token(line, &["start=", "scheduled="])
+}
+
+/// Find a `done=` or `@done(...)` completion date token in a task line
+fn parse_completed_token(line: &str) -> (Option<String>, Option<i64>) {
+    parse_date_token(line, &["done=", "@done("])
+}
+
+/// Find the first date token starting with one of `prefixes` in a line
+/// Accepts `YYYY-MM-DD` and `YYYY-MM-DDTHH:MM` values
+fn parse_date_token(line: &str, prefixes: &[&str]) -> (Option<String>, Option<i64>) {
+    for token in line.split(|c: char| c.is_whitespace() || c == '}' || c == '{') {
+        let value = match prefixes.iter().find_map(|p| token.strip_prefix(p)) {
+            Some(value) => value.trim_end_matches(')'),
+            None => continue,
+        };
+
+        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M") {
+            return (
+                Some(dt.format("%Y-%m-%d %H:%M").to_string()),
+                Some(dt.and_utc().timestamp()),
+            );
+        }
+        if let Ok(d) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            return (
+                Some(d.format("%Y-%m-%d").to_string()),
+                d.and_hms_opt(0, 0, 0).map(|dt| dt.and_utc().timestamp()),
+            );
+        }
+    }
+
+    (None, None)
+}
+
+/// Collect relative paths of all patto files under root
+pub(crate) fn collect_patto_files(root: &Path) -> std::io::Result<Vec<String>> {
+    let mut files = Vec::new();
+    collect_patto_files_recursive(root, root, None, None, &mut files)?;
     Ok(files)
 }
 
+/// Open the workspace repository for `.gitignore` checks when `enabled`
+/// Plain folders have no ignore rules, so nothing is skipped for them
+pub(crate) fn gitignore_repo(root: &Path, enabled: bool) -> Option<Repository> {
+    if !enabled {
+        return None;
+    }
+    Repository::open(root).ok()
+}
+
+/// Whether `relative` is excluded by the repository's ignore rules
+pub(crate) fn is_gitignored(gitignore: Option<&Repository>, relative: &Path) -> bool {
+    gitignore.is_some_and(|repo| repo.is_path_ignored(relative).unwrap_or(false))
+}
+
+/// Ignore file listing glob patterns (one per line) excluded from task scanning
+const TASK_IGNORE_FILE: &str = ".pattoignore";
+
+/// Parse `.pattoignore` in the workspace root, if there is one
+/// Patterns without a `/` match at any depth, and a matched directory excludes everything in it.
+fn load_task_ignore(root: &Path) -> Result<Option<GlobSet>, String> {
+    let Ok(content) = fs::read_to_string(root.join(TASK_IGNORE_FILE)) else {
+        return Ok(None);
+    };
+
+    let mut builder = GlobSetBuilder::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        // A leading or inner `/` anchors the pattern at the workspace root
+        let pattern = line.trim_start_matches('/').trim_end_matches('/');
+        let glob = if line.starts_with('/') || pattern.contains('/') {
+            pattern.to_string()
+        } else {
+            format!("**/{}", pattern)
+        };
+        builder.add(
+            Glob::new(&glob)
+                .map_err(|e| format!("Invalid pattern in {}: {}", TASK_IGNORE_FILE, e))?,
+        );
+    }
+
+    builder
+        .build()
+        .map(Some)
+        .map_err(|e| format!("Failed to parse {}: {}", TASK_IGNORE_FILE, e))
+}
+
+/// Whether `relative` or one of its directories matches `.pattoignore`
+fn is_task_ignored(ignore: Option<&GlobSet>, relative: &Path) -> bool {
+    ignore.is_some_and(|set| {
+        relative
+            .ancestors()
+            .filter(|p| !p.as_os_str().is_empty())
+            .any(|p| set.is_match(p))
+    })
+}
+
 fn collect_patto_files_recursive(
     root: &Path,
     dir: &Path,
+    ignore: Option<&GlobSet>,
+    gitignore: Option<&Repository>,
     files: &mut Vec<String>,
 ) -> std::io::Result<()> {
     for entry in fs::read_dir(dir)? {
@@ -127,20 +777,21 @@ fn collect_patto_files_recursive(
             continue;
         }
 
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        if ignore.is_some_and(|set| set.is_match(relative)) || is_gitignored(gitignore, relative) {
+            continue;
+        }
+
         if path.is_dir() {
-            collect_patto_files_recursive(root, &path, files)?;
+            collect_patto_files_recursive(root, &path, ignore, gitignore, files)?;
         } else if path.extension().map(|e| e == "pn").unwrap_or(false) {
-            let relative = path
-                .strip_prefix(root)
-                .map(|p| p.to_string_lossy().to_string())
-                .unwrap_or_else(|_| path.to_string_lossy().to_string());
-            files.push(relative);
+            files.push(relative.to_string_lossy().to_string());
         }
     }
     Ok(())
 }
 
-fn extract_tasks_from_content(content: &str, file_path: &str) -> Vec<TaskItem> {
+pub(crate) fn extract_tasks_from_content(content: &str, file_path: &str) -> Vec<TaskItem> {
     let parse_result = parser::parse_text(content);
     let mut tasks = Vec::new();
 
@@ -196,14 +847,42 @@ fn extract_tasks_from_ast(
                 // Extract line content
                 let line_content = node.extract_str().lines().next().unwrap_or("").to_string();
 
+                // Task line plus its indented sub-lines
+                let mut full_lines = vec![line_content.clone()];
+                for child in node.value().children.lock().unwrap().iter() {
+                    collect_child_lines(child, 1, &mut full_lines);
+                }
+                let full_content = full_lines.join("\n");
+                let (scheduled_date, scheduled_timestamp) = parse_scheduled_token(&line_content);
+                let recurrence_days = parse_recurrence(&line_content);
+                let priority = parse_priority(&line_content);
+
+                // Completion date from an explicit annotation, falling back to the deadline
+                let (completed_date, completed_timestamp) = match status {
+                    PattoTaskStatus::Done => match parse_completed_token(&line_content) {
+                        (Some(date), ts) => (Some(date), ts),
+                        (None, _) => (due_date.clone(), due_timestamp),
+                    },
+                    // On an open recurring task the annotation records the last completion
+                    _ if recurrence_days.is_some() => parse_completed_token(&line_content),
+                    _ => (None, None),
+                };
+
                 tasks.push(TaskItem {
                     file_path: file_path.to_string(),
                     file_name: file_name.to_string(),
                     line_number: location.row,
                     content: line_content,
+                    full_content,
                     status: status_str.to_string(),
                     due_date,
                     due_timestamp,
+                    scheduled_date,
+                    scheduled_timestamp,
+                    recurrence_days,
+                    priority,
+                    completed_date,
+                    completed_timestamp,
                 });
             }
         }
@@ -219,15 +898,27 @@ fn extract_tasks_from_ast(
     }
 }
 
+/// Collect the text of a line and its descendants, indented by depth
+fn collect_child_lines(node: &AstNode, depth: usize, lines: &mut Vec<String>) {
+    let text = node.extract_str().lines().next().unwrap_or("");
+    lines.push(format!("{}{}", "\t".repeat(depth), text));
+
+    for child in node.value().children.lock().unwrap().iter() {
+        collect_child_lines(child, depth + 1, lines);
+    }
+}
+
 /// Get tasks from a single file
 #[tauri::command]
 pub fn get_file_tasks(root: PathBuf, file_path: String) -> Result<Vec<TaskItem>, String> {
-    let full_path = root.join(&file_path);
+    let full_path = resolve_within_root(&root, &file_path)?;
 
     if !full_path.exists() {
         return Err(format!("File not found: {}", file_path));
     }
 
+    check_note_size(&full_path, &file_path, None)?;
+
     let content = fs::read_to_string(&full_path).map_err(|e| format!("Failed to read: {}", e))?;
     Ok(extract_tasks_from_content(&content, &file_path))
 }
@@ -242,25 +933,206 @@ pub struct TaskSummary {
     pub this_week: usize,
     pub later: usize,
     pub no_deadline: usize,
+    pub scheduled: usize,
     pub done: usize,
 }
 
 /// Get task summary counts
 #[tauri::command]
-pub async fn get_task_summary(root: PathBuf) -> Result<TaskSummary, String> {
-    let tasks = get_all_tasks(root).await?;
+pub async fn get_task_summary(app: AppHandle, root: PathBuf) -> Result<TaskSummary, String> {
+    let tasks = tokio::task::spawn_blocking(move || {
+        let cache = app.state::<TaskCache>();
+        aggregate_tasks(&root, None, Some(cache.inner()), None)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))??;
 
     Ok(TaskSummary {
         total: tasks.overdue.len()
             + tasks.today.len()
             + tasks.this_week.len()
             + tasks.later.len()
-            + tasks.no_deadline.len(),
+            + tasks.no_deadline.len()
+            + tasks.scheduled.len(),
         overdue: tasks.overdue.len(),
         today: tasks.today.len(),
         this_week: tasks.this_week.len(),
         later: tasks.later.len(),
         no_deadline: tasks.no_deadline.len(),
+        scheduled: tasks.scheduled.len(),
         done: tasks.done.len(),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn task(file_path: &str, line_number: usize) -> TaskItem {
+        TaskItem {
+            file_path: file_path.to_string(),
+            file_name: file_path.trim_end_matches(".pn").to_string(),
+            line_number,
+            content: format!("task {}", line_number),
+            full_content: format!("task {}", line_number),
+            status: "todo".to_string(),
+            due_date: None,
+            due_timestamp: None,
+            scheduled_date: None,
+            scheduled_timestamp: None,
+            recurrence_days: None,
+            priority: 0,
+            completed_date: None,
+            completed_timestamp: None,
+        }
+    }
+
+    /// Open task due on `due` (`YYYY-MM-DD` or `YYYY-MM-DDTHH:MM`) repeating every `days`
+    fn recurring(due: &str, completed: Option<&str>, days: u32) -> TaskItem {
+        let (due_date, due_timestamp) = parse_date_token(&format!("due={}", due), &["due="]);
+        let (completed_date, completed_timestamp) = match completed {
+            Some(done) => parse_completed_token(&format!("done={}", done)),
+            None => (None, None),
+        };
+        TaskItem {
+            due_date,
+            due_timestamp,
+            completed_date,
+            completed_timestamp,
+            recurrence_days: Some(days),
+            ..task("note.pn", 0)
+        }
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn recurrence_counts_from_last_completion() {
+        let mut task = recurring("2024-03-01", Some("2024-03-05"), 3);
+        roll_forward_recurrence(&mut task, date("2024-03-06"));
+        assert_eq!(task.due_date.as_deref(), Some("2024-03-08"));
+    }
+
+    #[test]
+    fn recurrence_from_completion_can_stay_overdue() {
+        let mut task = recurring("2024-03-01", Some("2024-03-02"), 3);
+        roll_forward_recurrence(&mut task, date("2024-03-20"));
+        assert_eq!(task.due_date.as_deref(), Some("2024-03-05"));
+    }
+
+    #[test]
+    fn recurrence_without_completion_rolls_to_today_or_later() {
+        let mut on_boundary = recurring("2024-01-01", None, 7);
+        roll_forward_recurrence(&mut on_boundary, date("2024-01-15"));
+        assert_eq!(on_boundary.due_date.as_deref(), Some("2024-01-15"));
+
+        let mut past_boundary = recurring("2024-01-01", None, 7);
+        roll_forward_recurrence(&mut past_boundary, date("2024-01-16"));
+        assert_eq!(past_boundary.due_date.as_deref(), Some("2024-01-22"));
+    }
+
+    #[test]
+    fn recurrence_rolls_over_month_end() {
+        let mut task = recurring("2024-01-30", None, 3);
+        roll_forward_recurrence(&mut task, date("2024-02-01"));
+        assert_eq!(task.due_date.as_deref(), Some("2024-02-02"));
+
+        let mut completed = recurring("2024-04-20", Some("2024-04-29"), 2);
+        roll_forward_recurrence(&mut completed, date("2024-04-30"));
+        assert_eq!(completed.due_date.as_deref(), Some("2024-05-01"));
+    }
+
+    #[test]
+    fn recurrence_rolls_over_leap_day() {
+        let mut leap = recurring("2024-02-20", Some("2024-02-27"), 2);
+        roll_forward_recurrence(&mut leap, date("2024-02-28"));
+        assert_eq!(leap.due_date.as_deref(), Some("2024-02-29"));
+
+        let mut common = recurring("2023-02-20", Some("2023-02-27"), 2);
+        roll_forward_recurrence(&mut common, date("2023-02-28"));
+        assert_eq!(common.due_date.as_deref(), Some("2023-03-01"));
+    }
+
+    #[test]
+    fn recurrence_keeps_time_of_day() {
+        let mut task = recurring("2024-01-31T09:30", None, 7);
+        roll_forward_recurrence(&mut task, date("2024-02-01"));
+        assert_eq!(task.due_date.as_deref(), Some("2024-02-07 09:30"));
+    }
+
+    #[test]
+    fn task_cache_invalidate_drops_entry() {
+        let cache = TaskCache::default();
+        let path = PathBuf::from("/ws/note.pn");
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1);
+
+        cache.insert(path.clone(), modified, vec![task("note.pn", 0)]);
+        assert_eq!(cache.get(&path, modified).map(|t| t.len()), Some(1));
+
+        cache.invalidate(&path);
+        assert!(cache.get(&path, modified).is_none());
+    }
+
+    #[test]
+    fn task_cache_concurrent_access() {
+        let cache = Arc::new(TaskCache::default());
+        let shared = PathBuf::from("/ws/shared.pn");
+
+        let handles: Vec<_> = (0..8)
+            .map(|thread| {
+                let cache = Arc::clone(&cache);
+                let shared = shared.clone();
+                std::thread::spawn(move || {
+                    let own = PathBuf::from(format!("/ws/note-{}.pn", thread));
+                    for i in 0..500u64 {
+                        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(i);
+                        let line = i as usize;
+
+                        // Entries only this thread writes must read back exactly
+                        cache.insert(own.clone(), modified, vec![task("own.pn", line)]);
+                        let cached = cache.get(&own, modified).expect("own entry");
+                        assert_eq!(cached[0].line_number, line);
+
+                        // The shared entry races with other threads but must never be torn
+                        cache.insert(shared.clone(), modified, vec![task("shared.pn", line)]);
+                        if let Some(cached) = cache.get(&shared, modified) {
+                            assert_eq!(cached[0].line_number, line);
+                        }
+                        if i % 7 == 0 {
+                            cache.invalidate(&shared);
+                        }
+                    }
+                    cache.invalidate(&own);
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        for thread in 0..8 {
+            let own = PathBuf::from(format!("/ws/note-{}.pn", thread));
+            assert!(cache.files.read().unwrap().get(&own).is_none());
+        }
+    }
+
+    #[test]
+    fn task_counts_skip_ignored_notes() {
+        let root = tempfile::tempdir().unwrap();
+        fs::write(root.path().join(".pattoignore"), "archive\n").unwrap();
+        fs::create_dir(root.path().join("archive")).unwrap();
+        let note = "a {@task status=todo}\nb {@task status=todo}\nc {@task status=done}\n";
+        fs::write(root.path().join("note.pn"), note).unwrap();
+        fs::write(root.path().join("archive/old.pn"), note).unwrap();
+        fs::write(root.path().join("image.png"), "").unwrap();
+
+        let paths = ["note.pn", "archive/old.pn", "image.png"].map(String::from);
+        let counts = task_counts(root.path(), &paths, None).unwrap();
+        assert_eq!(counts, [Some((2, 1)), None, None]);
+    }
+}
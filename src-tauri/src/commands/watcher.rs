@@ -0,0 +1,147 @@
+// Workspace file watching for patto-mobile
+// Emits events when notes change on disk, e.g. after a pull or an external sync
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+
+/// Quiet period after the last raw event before a batch is emitted
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Event payload for `file-created`, `file-changed` and `file-deleted`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChangeEvent {
+    /// Path relative to the watched root
+    pub path: String,
+}
+
+/// Watcher for the current workspace, dropped to stop watching
+#[derive(Default)]
+pub struct WatcherState {
+    watcher: Mutex<Option<RecommendedWatcher>>,
+}
+
+/// What happened to a file during a debounce window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Change {
+    Created,
+    Changed,
+    Deleted,
+}
+
+/// Watch `root` for changes to `.pn` files, replacing any previous watch
+/// Events are debounced and hidden paths such as `.git` are ignored.
+#[tauri::command]
+pub fn watch_workspace(
+    app: AppHandle,
+    state: State<'_, WatcherState>,
+    root: PathBuf,
+) -> Result<(), String> {
+    if !root.is_dir() {
+        return Err(format!("Workspace not found: {:?}", root));
+    }
+
+    // Events carry canonical paths on some platforms
+    let root = root.canonicalize().unwrap_or(root);
+
+    let (tx, rx) = mpsc::channel::<notify::Result<Event>>();
+    let mut watcher =
+        notify::recommended_watcher(tx).map_err(|e| format!("Failed to create watcher: {}", e))?;
+    watcher
+        .watch(&root, RecursiveMode::Recursive)
+        .map_err(|e| format!("Failed to watch {:?}: {}", root, e))?;
+
+    // Replacing the watcher drops the old sender, which ends the old thread
+    *state.watcher.lock().unwrap() = Some(watcher);
+
+    std::thread::spawn(move || {
+        // Blocks until the next burst of events, ends once the watcher is dropped
+        while let Ok(first) = rx.recv() {
+            let mut pending: HashMap<PathBuf, Change> = HashMap::new();
+            record_event(&root, first, &mut pending);
+
+            loop {
+                match rx.recv_timeout(DEBOUNCE) {
+                    Ok(event) => record_event(&root, event, &mut pending),
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+
+            for (relative, change) in pending {
+                // The last raw event may not reflect the final state after a burst
+                let change = match (change, root.join(&relative).exists()) {
+                    (Change::Deleted, true) => Change::Changed,
+                    (_, false) => Change::Deleted,
+                    (change, true) => change,
+                };
+                let event = match change {
+                    Change::Created => "file-created",
+                    Change::Changed => "file-changed",
+                    Change::Deleted => "file-deleted",
+                };
+                let _ = app.emit(
+                    event,
+                    FileChangeEvent {
+                        path: relative.to_string_lossy().to_string(),
+                    },
+                );
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Stop watching the workspace
+#[tauri::command]
+pub fn stop_watching(state: State<'_, WatcherState>) -> Result<(), String> {
+    state.watcher.lock().unwrap().take();
+    Ok(())
+}
+
+/// Merge a raw watcher event into the pending changes, keyed by relative path
+fn record_event(root: &Path, event: notify::Result<Event>, pending: &mut HashMap<PathBuf, Change>) {
+    let Ok(event) = event else {
+        return;
+    };
+
+    let change = match event.kind {
+        EventKind::Create(_) => Change::Created,
+        EventKind::Remove(_) => Change::Deleted,
+        // Renames report both names, existence is checked when the batch is emitted
+        EventKind::Modify(_) => Change::Changed,
+        _ => return,
+    };
+
+    for path in event.paths {
+        let Some(relative) = watched_note_path(root, &path) else {
+            continue;
+        };
+        // A file created and then written in the same burst is still new
+        let entry = pending.entry(relative).or_insert(change);
+        if !(*entry == Change::Created && change == Change::Changed) {
+            *entry = change;
+        }
+    }
+}
+
+/// Relative path of a `.pn` file outside hidden directories
+fn watched_note_path(root: &Path, path: &Path) -> Option<PathBuf> {
+    let relative = path.strip_prefix(root).ok()?;
+
+    let hidden = relative
+        .components()
+        .any(|c| c.as_os_str().to_string_lossy().starts_with('.'));
+    if hidden || !relative.extension().is_some_and(|e| e == "pn") {
+        return None;
+    }
+
+    Some(relative.to_path_buf())
+}
@@ -0,0 +1,89 @@
+// Image helpers shared by the note commands and the HTML renderer
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::fs;
+use std::path::Path;
+
+/// Read an image as a base64 data URL, with JPEG photos rotated upright
+pub(crate) fn image_data_url(path: &Path) -> Result<String, String> {
+    if !path.exists() {
+        return Err(format!("Image not found: {:?}", path));
+    }
+
+    let mut data = fs::read(path).map_err(|e| format!("Failed to read image: {}", e))?;
+
+    // Camera photos are often stored sideways with an EXIF orientation tag,
+    // rotate the pixels so the webview shows them upright
+    if image_mime_type(path) == "image/jpeg" {
+        if let Some(orientation) = exif_orientation(path).filter(|o| *o > 1) {
+            let img = image::load_from_memory(&data)
+                .map_err(|e| format!("Failed to decode image: {}", e))?;
+            let img = image::DynamicImage::ImageRgb8(apply_orientation(img, orientation).to_rgb8());
+
+            let mut encoded = std::io::Cursor::new(Vec::new());
+            img.write_to(&mut encoded, image::ImageFormat::Jpeg)
+                .map_err(|e| format!("Failed to encode image: {}", e))?;
+            data = encoded.into_inner();
+        }
+    }
+
+    let base64 = STANDARD.encode(&data);
+    Ok(format!("data:{};base64,{}", image_mime_type(path), base64))
+}
+
+/// Read the EXIF orientation tag (1-8) of an image, if present
+pub(crate) fn exif_orientation(path: &Path) -> Option<u32> {
+    let file = fs::File::open(path).ok()?;
+    let mut reader = std::io::BufReader::new(file);
+    let exif = exif::Reader::new().read_from_container(&mut reader).ok()?;
+    exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY)?
+        .value
+        .get_uint(0)
+}
+
+/// Rotate/flip an image according to its EXIF orientation
+pub(crate) fn apply_orientation(img: image::DynamicImage, orientation: u32) -> image::DynamicImage {
+    match orientation {
+        2 => img.fliph(),
+        3 => img.rotate180(),
+        4 => img.flipv(),
+        5 => img.rotate90().fliph(),
+        6 => img.rotate90(),
+        7 => img.rotate270().fliph(),
+        8 => img.rotate270(),
+        _ => img,
+    }
+}
+
+/// Determine image MIME type from file extension
+pub(crate) fn image_mime_type(path: &Path) -> &'static str {
+    // Cameras often write upper-case extensions like `IMG_1234.JPG`
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_mime_type_ignores_extension_case() {
+        assert_eq!(image_mime_type(Path::new("IMG_1234.JPG")), "image/jpeg");
+        assert_eq!(image_mime_type(Path::new("photo.Jpeg")), "image/jpeg");
+        assert_eq!(image_mime_type(Path::new("diagram.PNG")), "image/png");
+        assert_eq!(
+            image_mime_type(Path::new("notes.txt")),
+            "application/octet-stream"
+        );
+    }
+}
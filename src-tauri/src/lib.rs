@@ -2,14 +2,43 @@
 // A mobile app for viewing and editing patto notes
 
 mod commands;
+mod images;
 mod renderer;
 
-use commands::files::{create_file, delete_file, get_file_info, list_files, rename_file};
-use commands::git::{configure_remote, git_clone, git_init, git_pull, git_status, git_sync};
+use commands::analysis::{
+    find_duplicate_notes, get_link_extremes, rebuild_indices, vault_health_check, workspace_stats,
+};
+use commands::backup::{export_vault_zip, import_vault_zip};
+use commands::credentials::{load_credentials, store_credentials};
+use commands::files::{
+    clean_artifact_files, copy_file, create_file, delete_file, empty_trash, find_artifact_files,
+    get_file_info, get_file_info_batch, list_files, list_trash, list_tree, move_file, rename_file,
+    restore_file, LinkCache,
+};
+use commands::git::{
+    changes_since_last_sync, configure_remote, git_abort_merge, git_branches, git_cancel_clone,
+    git_checkout, git_clone, git_diff_file, git_init, git_initial_commit, git_log,
+    git_preview_sync, git_pull, git_remotes, git_reset_hard, git_status, git_sync,
+    normalize_timestamps, workspace_mode, CloneState, SyncState,
+};
 use commands::notes::{
-    extract_links, get_image_base64, read_note, render_content, render_note, write_note,
+    analyze_note_links, append_to_note, create_stub_notes, diff_against_disk, export_html_document,
+    export_outline_text, extract_links, extract_references, get_anchors, get_image_base64,
+    get_note_preview, get_random_note, get_thumbnail, list_missing_note_targets, merge_notes,
+    normalize_workspace_line_endings, parse_note_ast, read_note, render_content, render_note,
+    render_note_async, validate_note, write_note,
+};
+use commands::reminders::{clear_task_reminders, schedule_task_reminders, ReminderState};
+use commands::search::search_notes;
+use commands::secrets::scan_for_secrets;
+use commands::settings::{
+    get_git_identity, get_workspace_path, set_git_identity, set_workspace_path, SettingsState,
+};
+use commands::tasks::{
+    get_all_tasks, get_completed_tasks, get_deadline_histogram, get_due_soon, get_file_tasks,
+    get_task_calendar, get_task_summary, get_tasks_grouped_by_file, TaskCache,
 };
-use commands::tasks::{get_all_tasks, get_file_tasks, get_task_summary};
+use commands::watcher::{stop_watching, watch_workspace, WatcherState};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -17,31 +46,109 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_os::init())
+        .plugin(tauri_plugin_notification::init())
+        .manage(CloneState::default())
+        .manage(LinkCache::default())
+        .manage(ReminderState::default())
+        .manage(SettingsState::default())
+        .manage(SyncState::default())
+        .manage(TaskCache::default())
+        .manage(WatcherState::default())
         .invoke_handler(tauri::generate_handler![
             // Git commands
             git_clone,
+            git_cancel_clone,
             git_pull,
             git_sync,
             git_init,
+            git_initial_commit,
             git_status,
+            git_diff_file,
+            normalize_timestamps,
+            workspace_mode,
+            git_preview_sync,
+            changes_since_last_sync,
+            scan_for_secrets,
             configure_remote,
+            git_remotes,
+            git_log,
+            git_checkout,
+            git_branches,
+            git_reset_hard,
+            git_abort_merge,
+            // Credential commands
+            store_credentials,
+            load_credentials,
             // File commands
             list_files,
+            list_tree,
             get_file_info,
+            get_file_info_batch,
             create_file,
+            copy_file,
             delete_file,
+            list_trash,
+            restore_file,
+            empty_trash,
             rename_file,
+            move_file,
+            find_artifact_files,
+            clean_artifact_files,
             // Note commands
             read_note,
             write_note,
+            append_to_note,
+            normalize_workspace_line_endings,
             render_note,
+            render_note_async,
+            get_random_note,
             render_content,
+            get_note_preview,
+            diff_against_disk,
+            export_html_document,
+            export_outline_text,
+            parse_note_ast,
             extract_links,
+            extract_references,
+            analyze_note_links,
+            get_anchors,
+            validate_note,
+            list_missing_note_targets,
+            create_stub_notes,
+            merge_notes,
             get_image_base64,
+            get_thumbnail,
+            // Search commands
+            search_notes,
             // Task commands
             get_all_tasks,
             get_file_tasks,
             get_task_summary,
+            get_task_calendar,
+            get_tasks_grouped_by_file,
+            get_completed_tasks,
+            get_due_soon,
+            get_deadline_histogram,
+            // Reminder commands
+            schedule_task_reminders,
+            clear_task_reminders,
+            // Analysis commands
+            find_duplicate_notes,
+            get_link_extremes,
+            vault_health_check,
+            rebuild_indices,
+            workspace_stats,
+            // Backup commands
+            export_vault_zip,
+            import_vault_zip,
+            // Watcher commands
+            watch_workspace,
+            stop_watching,
+            // Settings commands
+            get_workspace_path,
+            set_workspace_path,
+            get_git_identity,
+            set_git_identity,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -1,17 +1,76 @@
 // Mobile-optimized HTML renderer for patto notes
 // Generates clean HTML without inline styles for easier CSS styling
 
+use crate::commands::files::resolve_within_root;
+use crate::images::image_data_url;
 use patto::parser::{AstNode, AstNodeKind, Property, TaskStatus};
+use serde::{Deserialize, Serialize};
 use std::io::{self, Write};
 use std::path::Path;
 
+/// How local image paths are written into the rendered HTML
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ImagePathStrategy {
+    /// `https://asset.localhost/<absolute path>` for the Tauri asset protocol
+    #[default]
+    AssetProtocol,
+    /// Embed the image as a base64 data URL
+    Base64Inline,
+    /// Leave the path exactly as written in the note
+    RawRelative,
+}
+
+/// Host used for YouTube embeds
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum YoutubeHost {
+    /// www.youtube.com
+    #[default]
+    Standard,
+    /// www.youtube-nocookie.com (privacy-enhanced mode)
+    NoCookie,
+}
+
+impl YoutubeHost {
+    fn embed_url(&self, video_id: &str) -> String {
+        match self {
+            YoutubeHost::Standard => format!("https://www.youtube.com/embed/{}", video_id),
+            YoutubeHost::NoCookie => format!("https://www.youtube-nocookie.com/embed/{}", video_id),
+        }
+    }
+}
+
+/// Options controlling the rendered HTML
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RenderOptions {
+    pub image_strategy: ImagePathStrategy,
+    pub youtube_host: YoutubeHost,
+    /// Emit a thumbnail placeholder with `data-video-id` instead of an iframe
+    pub youtube_click_to_load: bool,
+    /// Turn bare http(s) URLs in plain text into links
+    pub autolink: bool,
+    /// Soft-wrap code blocks (`wrap` class plus `<wbr>` hints in long tokens)
+    pub wrap_code: bool,
+}
+
 pub struct MobileHtmlRenderer {
     workspace_path: Option<String>,
+    options: RenderOptions,
 }
 
 impl MobileHtmlRenderer {
     pub fn new(workspace_path: Option<String>) -> Self {
-        Self { workspace_path }
+        Self {
+            workspace_path,
+            options: RenderOptions::default(),
+        }
+    }
+
+    pub fn with_options(mut self, options: RenderOptions) -> Self {
+        self.options = options;
+        self
     }
 
     pub fn render(&self, ast: &AstNode) -> io::Result<String> {
@@ -32,10 +91,12 @@ impl MobileHtmlRenderer {
             }
             AstNodeKind::Line { properties } | AstNodeKind::QuoteContent { properties } => {
                 let class = self.get_line_class(properties);
+                // Source row for editor/preview sync-scrolling and tap-to-edit
+                let row = ast.location().row;
                 write!(
                     output,
-                    "<div class=\"patto-line{}\" data-depth=\"{}\">",
-                    class, depth
+                    "<div class=\"patto-line{}\" data-depth=\"{}\" data-line=\"{}\">",
+                    class, depth, row
                 )?;
 
                 // Task checkbox
@@ -113,14 +174,30 @@ impl MobileHtmlRenderer {
                     }
                     write!(output, "</code>")?;
                 } else {
+                    let wrap_class = if self.options.wrap_code { " wrap" } else { "" };
                     write!(
                         output,
-                        "<pre class=\"code-block\" data-lang=\"{}\"><code>",
-                        lang
+                        "<pre class=\"code-block{}\" data-lang=\"{}\"><code>",
+                        wrap_class, lang
                     )?;
                     let children = ast.value().children.lock().unwrap();
+                    let mut prev_row: Option<usize> = None;
                     for child in children.iter() {
-                        writeln!(output, "{}", html_escape(child.extract_str()))?;
+                        // Blank lines inside the block have no node, restore them from row gaps
+                        let row = child.location().row;
+                        if let Some(prev_row) = prev_row {
+                            for _ in prev_row + 1..row {
+                                writeln!(output)?;
+                            }
+                        }
+                        prev_row = Some(row);
+
+                        let line = code_block_line(child, depth + 1);
+                        if self.options.wrap_code {
+                            writeln!(output, "{}", code_line_with_breaks(&line))?;
+                        } else {
+                            writeln!(output, "{}", html_escape(&line))?;
+                        }
                     }
                     write!(output, "</code></pre>")?;
                 }
@@ -181,11 +258,22 @@ impl MobileHtmlRenderer {
                 // Check for YouTube, Twitter embeds
                 if link.contains("youtube.com") || link.contains("youtu.be") {
                     if let Some(video_id) = extract_youtube_id(link) {
-                        write!(
-                            output,
-                            "<div class=\"video-embed\"><iframe src=\"https://www.youtube.com/embed/{}\" frameborder=\"0\" allowfullscreen></iframe></div>",
-                            video_id
-                        )?;
+                        if self.options.youtube_click_to_load {
+                            write!(
+                                output,
+                                "<div class=\"video-embed video-placeholder\" data-video-id=\"{}\" data-embed-src=\"{}\"><img src=\"https://img.youtube.com/vi/{}/hqdefault.jpg\" alt=\"{}\" loading=\"lazy\"/></div>",
+                                video_id,
+                                self.options.youtube_host.embed_url(&video_id),
+                                video_id,
+                                html_escape(display)
+                            )?;
+                        } else {
+                            write!(
+                                output,
+                                "<div class=\"video-embed\"><iframe src=\"{}\" frameborder=\"0\" allowfullscreen></iframe></div>",
+                                self.options.youtube_host.embed_url(&video_id)
+                            )?;
+                        }
                     } else {
                         write!(
                             output,
@@ -201,38 +289,17 @@ impl MobileHtmlRenderer {
                     )?;
                 }
             }
-            AstNodeKind::Decoration {
-                fontsize,
-                italic,
-                underline,
-                deleted,
-            } => {
-                let mut classes = Vec::new();
-                if *fontsize > 0 {
-                    classes.push("bold");
-                }
-                if *fontsize < 0 {
-                    classes.push("small");
-                }
-                if *italic {
-                    classes.push("italic");
-                }
-                if *underline {
-                    classes.push("underline");
-                }
-                if *deleted {
-                    classes.push("deleted");
-                }
-
-                let class_str = classes.join(" ");
-                write!(output, "<span class=\"decoration {}\">", class_str)?;
-                let contents = ast.value().contents.lock().unwrap();
-                for content in contents.iter() {
-                    self.render_node(content, output, depth)?;
+            AstNodeKind::Decoration { .. } => {
+                self.render_decoration(ast, output, depth, DecorationStyle::default())?;
+            }
+            AstNodeKind::Text => {
+                if self.options.autolink {
+                    write!(output, "{}", render_autolinks(ast.extract_str()))?;
+                } else {
+                    write!(output, "{}", render_highlights(ast.extract_str()))?;
                 }
-                write!(output, "</span>")?;
             }
-            AstNodeKind::Text | AstNodeKind::CodeContent | AstNodeKind::MathContent => {
+            AstNodeKind::CodeContent | AstNodeKind::MathContent => {
                 write!(output, "{}", html_escape(ast.extract_str()))?;
             }
             AstNodeKind::HorizontalLine => {
@@ -270,6 +337,45 @@ impl MobileHtmlRenderer {
         Ok(())
     }
 
+    /// Render a decoration, carrying the styles of enclosing decorations
+    /// so nested spans keep every applicable class
+    fn render_decoration(
+        &self,
+        ast: &AstNode,
+        output: &mut dyn Write,
+        depth: usize,
+        inherited: DecorationStyle,
+    ) -> io::Result<()> {
+        let style = match ast.kind() {
+            AstNodeKind::Decoration {
+                fontsize,
+                italic,
+                underline,
+                deleted,
+            } => DecorationStyle {
+                // The innermost font size wins
+                bold: *fontsize > 0 || (*fontsize == 0 && inherited.bold),
+                small: *fontsize < 0 || (*fontsize == 0 && inherited.small),
+                italic: *italic || inherited.italic,
+                underline: *underline || inherited.underline,
+                deleted: *deleted || inherited.deleted,
+            },
+            _ => return self.render_node(ast, output, depth),
+        };
+
+        write!(output, "<span class=\"decoration {}\">", style.classes())?;
+        let contents = ast.value().contents.lock().unwrap();
+        for content in contents.iter() {
+            if matches!(content.kind(), AstNodeKind::Decoration { .. }) {
+                self.render_decoration(content, output, depth, style)?;
+            } else {
+                self.render_node(content, output, depth)?;
+            }
+        }
+        write!(output, "</span>")?;
+        Ok(())
+    }
+
     fn get_line_class(&self, properties: &[Property]) -> String {
         let mut classes = Vec::new();
         for property in properties {
@@ -295,39 +401,363 @@ impl MobileHtmlRenderer {
             return src.to_string();
         }
 
-        // For local paths, resolve relative to workspace and use Tauri asset protocol
-        if let Some(workspace) = &self.workspace_path {
-            let full_path = Path::new(workspace).join(src);
+        // For local paths, resolve relative to workspace
+        let workspace = match (&self.workspace_path, self.options.image_strategy) {
+            (Some(workspace), strategy) if strategy != ImagePathStrategy::RawRelative => workspace,
+            _ => return src.to_string(),
+        };
+        // Notes come from shared remotes, never point outside the workspace
+        let Ok(full_path) = resolve_within_root(Path::new(workspace), src) else {
+            return src.to_string();
+        };
+
+        match self.options.image_strategy {
             // Use https://asset.localhost for Tauri 2 asset protocol
-            format!("https://asset.localhost/{}", full_path.display())
+            ImagePathStrategy::AssetProtocol => {
+                format!("https://asset.localhost/{}", full_path.display())
+            }
+            // Fall back to the asset URL if the file can't be read
+            ImagePathStrategy::Base64Inline => image_data_url(&full_path)
+                .unwrap_or_else(|_| format!("https://asset.localhost/{}", full_path.display())),
+            ImagePathStrategy::RawRelative => src.to_string(),
+        }
+    }
+}
+
+/// Source text of a code block line with the block's own indentation removed
+/// Indentation beyond the block level (e.g. Python bodies) is kept verbatim
+fn code_block_line(node: &AstNode, block_indent: usize) -> String {
+    let text = node.extract_str();
+    let source: &str = &node.location().input;
+    let source = source.trim_end_matches(['\r', '\n']);
+
+    // Fall back to the extracted text if the source line doesn't contain it
+    if !source.ends_with(text) {
+        return text.to_string();
+    }
+
+    let mut line = source;
+    for _ in 0..block_indent {
+        match line.strip_prefix('\t') {
+            Some(rest) => line = rest,
+            None => break,
+        }
+    }
+    line.to_string()
+}
+
+/// Tokens longer than this get `<wbr>` break opportunities when wrapping code
+const WRAP_TOKEN_LENGTH: usize = 30;
+
+/// Escape a code line, adding `<wbr>` after separators inside very long tokens
+fn code_line_with_breaks(line: &str) -> String {
+    let mut html = String::new();
+    let mut token = String::new();
+
+    for c in line.chars() {
+        if c.is_whitespace() {
+            push_code_token(&mut token, &mut html);
+            html.push(c);
         } else {
-            src.to_string()
+            token.push(c);
         }
     }
+    push_code_token(&mut token, &mut html);
+
+    html
 }
 
-fn html_escape(s: &str) -> String {
+fn push_code_token(token: &mut String, html: &mut String) {
+    if token.chars().count() > WRAP_TOKEN_LENGTH {
+        for c in token.chars() {
+            html.push_str(&html_escape(&c.to_string()));
+            if matches!(c, ',' | '/' | '.' | ';' | '&' | '?' | '=' | '_' | '-') {
+                html.push_str("<wbr>");
+            }
+        }
+    } else {
+        html.push_str(&html_escape(token));
+    }
+    token.clear();
+}
+
+/// Combined styles of a decoration and the decorations around it
+#[derive(Debug, Clone, Copy, Default)]
+struct DecorationStyle {
+    bold: bool,
+    small: bool,
+    italic: bool,
+    underline: bool,
+    deleted: bool,
+}
+
+impl DecorationStyle {
+    fn classes(&self) -> String {
+        let mut classes = Vec::new();
+        if self.bold {
+            classes.push("bold");
+        }
+        if self.small {
+            classes.push("small");
+        }
+        if self.italic {
+            classes.push("italic");
+        }
+        if self.underline {
+            classes.push("underline");
+        }
+        if self.deleted {
+            classes.push("deleted");
+        }
+        classes.join(" ")
+    }
+}
+
+pub(crate) fn html_escape(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
         .replace('"', "&quot;")
 }
 
-fn extract_youtube_id(url: &str) -> Option<String> {
-    // Handle youtube.com/watch?v=ID
-    if let Some(pos) = url.find("v=") {
-        let id_start = pos + 2;
-        let id = &url[id_start..];
-        let id_end = id.find('&').unwrap_or(id.len());
-        return Some(id[..id_end].to_string());
+/// Escape text, wrapping `==marked==` passages in `<mark>`
+/// The patto parser has no highlight decoration, so this is done on plain text
+fn render_highlights(s: &str) -> String {
+    let mut html = String::new();
+    let mut rest = s;
+
+    while let Some(start) = rest.find("==") {
+        let after_open = &rest[start + 2..];
+        match after_open.find("==") {
+            Some(len) if len > 0 => {
+                html.push_str(&html_escape(&rest[..start]));
+                html.push_str("<mark class=\"highlight\">");
+                html.push_str(&html_escape(&after_open[..len]));
+                html.push_str("</mark>");
+                rest = &after_open[len + 2..];
+            }
+            _ => break,
+        }
     }
-    // Handle youtu.be/ID
-    if url.contains("youtu.be/") {
-        if let Some(pos) = url.rfind('/') {
-            let id = &url[pos + 1..];
-            let id_end = id.find('?').unwrap_or(id.len());
-            return Some(id[..id_end].to_string());
+
+    html.push_str(&html_escape(rest));
+    html
+}
+
+/// Wrap bare http(s) URLs in links, rendering the surrounding text as usual
+fn render_autolinks(s: &str) -> String {
+    let mut html = String::new();
+    let mut rest = s;
+
+    while let Some(start) = find_url_start(rest) {
+        let candidate = &rest[start..];
+        let end = candidate
+            .find(char::is_whitespace)
+            .unwrap_or(candidate.len());
+        // Trailing punctuation usually belongs to the sentence, not the URL
+        let url = candidate[..end].trim_end_matches(['.', ',', ';', ':', '!', '?', ')', '\'', '"']);
+
+        html.push_str(&render_highlights(&rest[..start]));
+        html.push_str(&format!(
+            "<a class=\"external-link\" href=\"{}\">{}</a>",
+            html_escape(url),
+            html_escape(url)
+        ));
+        rest = &rest[start + url.len()..];
+    }
+
+    html.push_str(&render_highlights(rest));
+    html
+}
+
+/// Byte offset of the first `http://` or `https://` followed by at least one character
+fn find_url_start(s: &str) -> Option<usize> {
+    let mut offset = 0;
+    while let Some(pos) = s[offset..].find("http") {
+        let start = offset + pos;
+        let after = &s[start..];
+        for scheme in ["https://", "http://"] {
+            if let Some(rest) = after.strip_prefix(scheme) {
+                if rest.starts_with(|c: char| !c.is_whitespace()) {
+                    return Some(start);
+                }
+            }
         }
+        offset = start + "http".len();
     }
     None
 }
+
+fn extract_youtube_id(url: &str) -> Option<String> {
+    // Split into host, path and query, dropping scheme and fragment
+    let rest = url.split_once("://").map(|(_, r)| r).unwrap_or(url);
+    let rest = rest.split('#').next().unwrap_or(rest);
+    let (host_path, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let (host, path) = host_path.split_once('/').unwrap_or((host_path, ""));
+    let host = host.trim_start_matches("www.").trim_start_matches("m.");
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    let id = match host {
+        // youtu.be/ID
+        "youtu.be" => segments.first().copied(),
+        "youtube.com" | "music.youtube.com" | "youtube-nocookie.com" => match segments.as_slice() {
+            // youtube.com/watch?v=ID, `v` looked up as a whole query parameter
+            ["watch"] => query.split('&').find_map(|param| param.strip_prefix("v=")),
+            // youtube.com/shorts/ID, /live/ID, /embed/ID, /v/ID
+            ["shorts" | "live" | "embed" | "v", id, ..] => Some(*id),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    id.filter(|id| {
+        !id.is_empty()
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    })
+    .map(|id| id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use patto::parser;
+
+    fn render(text: &str) -> String {
+        let result = parser::parse_text(text);
+        MobileHtmlRenderer::new(None).render(&result.ast).unwrap()
+    }
+
+    #[test]
+    fn triply_nested_decorations_keep_outer_classes() {
+        let html = render("[* outer [/ middle [_ inner]]]\n");
+
+        let outer = html.find("<span class=\"decoration bold\">").unwrap();
+        let middle = html
+            .find("<span class=\"decoration bold italic\">")
+            .unwrap();
+        let inner = html
+            .find("<span class=\"decoration bold italic underline\">")
+            .unwrap();
+        assert!(outer < middle && middle < inner);
+        assert!(html[inner..].contains("inner"));
+    }
+
+    #[test]
+    fn code_block_keeps_relative_indentation() {
+        let note =
+            "[@code python]\n\tdef check(x):\n\t    if x:\n\t        return 1\n\n\t    return 0\n";
+        let html = render(note);
+
+        assert!(html.contains("data-lang=\"python\""));
+        assert!(html.contains("def check(x):\n    if x:\n        return 1\n\n    return 0\n"));
+    }
+
+    #[test]
+    fn nested_code_block_strips_only_block_indentation() {
+        let html = render("snippet\n\t[@code python]\n\t\tdef check():\n\t\t    return 1\n");
+
+        assert!(html.contains("def check():\n    return 1\n"));
+    }
+
+    #[test]
+    fn youtube_id_from_watch_url() {
+        assert_eq!(
+            extract_youtube_id("https://www.youtube.com/watch?v=dQw4w9WgXcQ").as_deref(),
+            Some("dQw4w9WgXcQ")
+        );
+        // `v` is found regardless of its position, and not inside other parameters
+        assert_eq!(
+            extract_youtube_id("https://youtube.com/watch?list=PLx&dev=1&v=dQw4w9WgXcQ&t=42")
+                .as_deref(),
+            Some("dQw4w9WgXcQ")
+        );
+        assert_eq!(
+            extract_youtube_id("https://www.youtube.com/watch?dev=abc"),
+            None
+        );
+    }
+
+    #[test]
+    fn youtube_id_from_short_link() {
+        assert_eq!(
+            extract_youtube_id("https://youtu.be/dQw4w9WgXcQ?t=42").as_deref(),
+            Some("dQw4w9WgXcQ")
+        );
+    }
+
+    #[test]
+    fn youtube_id_from_embed_url() {
+        assert_eq!(
+            extract_youtube_id("https://www.youtube.com/embed/dQw4w9WgXcQ").as_deref(),
+            Some("dQw4w9WgXcQ")
+        );
+        assert_eq!(
+            extract_youtube_id("https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ").as_deref(),
+            Some("dQw4w9WgXcQ")
+        );
+    }
+
+    #[test]
+    fn youtube_id_from_shorts_url() {
+        assert_eq!(
+            extract_youtube_id("https://m.youtube.com/shorts/dQw4w9WgXcQ").as_deref(),
+            Some("dQw4w9WgXcQ")
+        );
+    }
+
+    #[test]
+    fn youtube_id_from_live_url() {
+        assert_eq!(
+            extract_youtube_id("https://www.youtube.com/live/dQw4w9WgXcQ?si=abc").as_deref(),
+            Some("dQw4w9WgXcQ")
+        );
+    }
+
+    #[test]
+    fn youtube_id_from_v_url() {
+        assert_eq!(
+            extract_youtube_id("https://www.youtube.com/v/dQw4w9WgXcQ").as_deref(),
+            Some("dQw4w9WgXcQ")
+        );
+    }
+
+    fn inline_renderer(workspace: &Path) -> MobileHtmlRenderer {
+        MobileHtmlRenderer::new(Some(workspace.to_string_lossy().to_string())).with_options(
+            RenderOptions {
+                image_strategy: ImagePathStrategy::Base64Inline,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn base64_images_are_inlined_from_the_workspace() {
+        let workspace = tempfile::tempdir().unwrap();
+        std::fs::write(workspace.path().join("pic.png"), b"png").unwrap();
+
+        let url = inline_renderer(workspace.path()).resolve_image_path("pic.png");
+        assert!(url.starts_with("data:image/png;base64,"));
+    }
+
+    #[test]
+    fn base64_images_outside_the_workspace_are_not_read() {
+        let parent = tempfile::tempdir().unwrap();
+        let workspace = parent.path().join("notes");
+        std::fs::create_dir(&workspace).unwrap();
+        std::fs::write(parent.path().join("secret.png"), b"secret").unwrap();
+        let outside = parent
+            .path()
+            .join("secret.png")
+            .to_string_lossy()
+            .to_string();
+
+        let renderer = inline_renderer(&workspace);
+        assert_eq!(
+            renderer.resolve_image_path("../secret.png"),
+            "../secret.png"
+        );
+        assert_eq!(renderer.resolve_image_path(&outside), outside);
+    }
+}
@@ -34,6 +34,7 @@ export const useStore = create(
 
             // === Files ===
             files: [],
+            filesTotal: 0,
             sortBy: SortBy.LAST_MODIFIED,
             isLoadingFiles: false,
 
@@ -70,8 +71,8 @@ export const useStore = create(
 
                 set({ isLoadingFiles: true });
                 try {
-                    const files = await invoke('list_files', { root: workspacePath, sortBy });
-                    set({ files, isLoadingFiles: false });
+                    const result = await invoke('list_files', { root: workspacePath, sortBy });
+                    set({ files: result.entries, filesTotal: result.total, isLoadingFiles: false });
                 } catch (error) {
                     console.error('Failed to load files:', error);
                     set({ isLoadingFiles: false });